@@ -0,0 +1,93 @@
+use dotenv;
+use std::env;
+use std::net::SocketAddr;
+use std::path::Path;
+
+pub const DEFAULT_LISTEN: &'static str = "0.0.0.0:9594";
+
+/// How the exporter finds the server it scrapes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerSelection {
+    /// name of a server device of the account, looked up on plex.tv
+    Device(String),
+    /// address of the server, plex.tv is not involved
+    Address(SocketAddr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub token: String,
+    pub server: ServerSelection,
+    /// address the metrics are served on
+    pub listen: SocketAddr,
+}
+
+impl Config {
+    /// Reads the config from the environment after loading the file at `path`,
+    /// or a `.env` file in the working directory if there is one
+    pub fn load(path: Option<&str>) -> Result<Config, String> {
+        match path {
+            Some(p) => dotenv::from_path(Path::new(p))
+                .map_err(|e| format!("Failed to load the config file {}: {:?}", p, e))?,
+            _ => {
+                dotenv::dotenv().ok();
+            }
+        }
+        Config::from_vars(|key| env::var(key).ok())
+    }
+
+    pub fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Result<Config, String> {
+        let token = var("PLEX_TOKEN").ok_or("PLEX_TOKEN is not set")?;
+        let server = match (var("PLEX_SERVER_ADDRESS"), var("PLEX_SERVER")) {
+            (Some(addr), _) => ServerSelection::Address(addr.parse()
+                .map_err(|_| format!("PLEX_SERVER_ADDRESS is not a valid address: {}", addr))?),
+            (_, Some(name)) => ServerSelection::Device(name),
+            _ => return Err("Either PLEX_SERVER or PLEX_SERVER_ADDRESS has to be set".to_string()),
+        };
+        let listen = var("EXPORTER_LISTEN").unwrap_or(DEFAULT_LISTEN.to_string());
+        let listen = listen
+            .parse()
+            .map_err(|_| format!("EXPORTER_LISTEN is not a valid address: {}", listen))?;
+        Ok(Config {
+            token,
+            server,
+            listen,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> Result<Config, String> {
+        let vars = vars.iter()
+            .map(|&(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        Config::from_vars(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn config_from_vars() {
+        let c = config(&[("PLEX_TOKEN", "token"), ("PLEX_SERVER", "Cloud")]).unwrap();
+        assert_eq!(c.server, ServerSelection::Device("Cloud".to_string()));
+        assert_eq!(c.listen, DEFAULT_LISTEN.parse().unwrap());
+
+        let c = config(&[
+            ("PLEX_TOKEN", "token"),
+            ("PLEX_SERVER", "Cloud"),
+            ("PLEX_SERVER_ADDRESS", "127.0.0.1:32400"),
+            ("EXPORTER_LISTEN", "127.0.0.1:9000"),
+        ]).unwrap();
+        assert_eq!(
+            c.server,
+            ServerSelection::Address("127.0.0.1:32400".parse().unwrap())
+        );
+        assert_eq!(c.listen, "127.0.0.1:9000".parse().unwrap());
+
+        assert!(config(&[("PLEX_SERVER", "Cloud")]).is_err());
+        assert!(config(&[("PLEX_TOKEN", "token")]).is_err());
+        assert!(config(&[("PLEX_TOKEN", "token"), ("PLEX_SERVER_ADDRESS", "cloud")]).is_err());
+    }
+}
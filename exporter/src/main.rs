@@ -0,0 +1,234 @@
+extern crate dotenv;
+extern crate env_logger;
+extern crate futures;
+extern crate hyper;
+extern crate hyper_tls;
+#[macro_use]
+extern crate log;
+extern crate plexapi;
+extern crate tokio_core;
+
+mod config;
+mod metrics;
+
+use config::{Config, ServerSelection};
+use futures::future::{self, Either};
+use futures::{Future, Stream};
+use hyper::{Body, Client, Method, StatusCode};
+use hyper::client::HttpConnector;
+use hyper::header::{ContentLength, ContentType};
+use hyper::server::{Http, Request, Response, Service};
+use hyper_tls::HttpsConnector;
+use metrics::{Metrics, Sample};
+use plexapi::client::Plex;
+use plexapi::errors::APIError;
+use plexapi::types::device::Connection;
+use plexapi::types::feature::Feature;
+use plexapi::types::server::{Identity, PlexServer};
+use plexapi::types::statistics::StatisticsTimespan;
+use std::{env, io, process};
+use tokio_core::reactor::Core;
+
+pub const METRICS_PATH: &'static str = "/metrics";
+
+type HttpsClient = Client<HttpsConnector<HttpConnector>, Body>;
+
+/// Serves the metrics of the configured server, every scrape queries the server anew
+#[derive(Clone)]
+struct Exporter<'a> {
+    plex: Plex<'a>,
+    client: &'a HttpsClient,
+    config: &'a Config,
+}
+
+impl<'a> Exporter<'a> {
+    fn server(&self) -> Box<Future<Item = (PlexServer<'a>, Vec<Connection>), Error = APIError> + 'a> {
+        match self.config.server {
+            ServerSelection::Address(addr) => Box::new(
+                self.plex
+                    .connect(addr.to_string().as_str())
+                    .map(move |server| (server, vec![Connection::from_endoint(addr)])),
+            ),
+            ServerSelection::Device(ref name) => {
+                Box::new(self.plex.select_device(name.as_str()).and_then(|device| {
+                    let connections = device.inner.connections.clone();
+                    device.connect().map(move |server| (server, connections))
+                }))
+            }
+        }
+    }
+
+    fn collect(&self) -> Box<Future<Item = Metrics, Error = APIError> + 'a> {
+        let client = self.client;
+        Box::new(self.server().and_then(move |(server, connections)| {
+            let sections = server
+                .library()
+                .and_then(|library| library.sections())
+                .and_then(|sections| {
+                    future::join_all(sections.into_iter().map(|section| {
+                        let labels = vec![
+                            ("section", section.inner.title.clone()),
+                            ("type", section.inner.type_.clone()),
+                        ];
+                        section
+                            .item_count()
+                            .map(move |count| Sample::new(labels, count as f64))
+                    }))
+                });
+
+            let sessions = server.sessions().map(|sessions| {
+                let transcodes = sessions.iter().filter(|s| s.is_transcoding()).count();
+                (sessions.len(), transcodes)
+            });
+
+            // older servers have no statistics, the metric is left out for them
+            let bandwidth = match server.supports(Feature::Statistics) {
+                true => Either::A(
+                    server
+                        .bandwidth_statistics(StatisticsTimespan::Seconds)
+                        .map(Some),
+                ),
+                _ => Either::B(future::ok(None)),
+            };
+
+            let reachability = future::join_all(connections.into_iter().map(move |conn| {
+                Identity::probe(client, &conn).then(move |identity| {
+                    let labels = vec![("uri", conn.endpoint())];
+                    Ok::<_, APIError>(Sample::new(labels, identity.is_ok() as u8 as f64))
+                })
+            }));
+
+            sections.join4(sessions, bandwidth, reachability).map(
+                move |(sections, (sessions, transcodes), bandwidth, reachability)| {
+                    let mut metrics = Metrics::new();
+                    metrics.gauge(
+                        "plex_up",
+                        "Whether the server could be scraped",
+                        vec![Sample::value(1.0)],
+                    );
+                    metrics.gauge(
+                        "plex_server_info",
+                        "Name, version and platform of the server",
+                        vec![
+                            Sample::new(
+                                vec![
+                                    ("name", server.inner.friendly_name().to_string()),
+                                    ("version", server.inner.version().to_string()),
+                                    ("platform", server.inner.platform().to_string()),
+                                ],
+                                1.0,
+                            ),
+                        ],
+                    );
+                    metrics.gauge(
+                        "plex_library_section_items",
+                        "Number of items in a library section",
+                        sections,
+                    );
+                    metrics.gauge(
+                        "plex_sessions",
+                        "Number of active playback sessions",
+                        vec![Sample::value(sessions as f64)],
+                    );
+                    metrics.gauge(
+                        "plex_transcode_sessions",
+                        "Number of playback sessions that are transcoded",
+                        vec![Sample::value(transcodes as f64)],
+                    );
+                    if let Some(bandwidth) = bandwidth {
+                        let samples = bandwidth
+                            .bytes_per_account()
+                            .into_iter()
+                            .map(|(id, bytes)| {
+                                let account = bandwidth
+                                    .account(id.as_str())
+                                    .map(|a| a.name.clone())
+                                    .unwrap_or(id);
+                                Sample::new(vec![("account", account)], bytes as f64)
+                            })
+                            .collect();
+                        metrics.gauge(
+                            "plex_bandwidth_bytes",
+                            "Bytes sent per account during the recent window of the server statistics",
+                            samples,
+                        );
+                    }
+                    metrics.gauge(
+                        "plex_connection_up",
+                        "Whether a connection of the server answers",
+                        reachability,
+                    );
+                    metrics
+                },
+            )
+        }))
+    }
+}
+
+impl<'a> Service for Exporter<'a> {
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response, Error = hyper::Error> + 'a>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        if req.method() != &Method::Get || req.path() != METRICS_PATH {
+            return Box::new(future::ok(Response::new().with_status(StatusCode::NotFound)));
+        }
+        Box::new(self.collect().then(|metrics| {
+            let metrics = metrics.unwrap_or_else(|e| {
+                warn!("Failed to scrape the server: {}", e);
+                let mut metrics = Metrics::new();
+                metrics.gauge(
+                    "plex_up",
+                    "Whether the server could be scraped",
+                    vec![Sample::value(0.0)],
+                );
+                metrics
+            });
+            let body = metrics.render();
+            Ok(Response::new()
+                .with_header(ContentType::plaintext())
+                .with_header(ContentLength(body.len() as u64))
+                .with_body(body))
+        }))
+    }
+}
+
+fn main() {
+    env_logger::init().unwrap();
+    // an optional path to a config file, otherwise `.env` is used
+    let path = env::args().nth(1);
+    let config = match Config::load(path.as_ref().map(|p| p.as_str())) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut core = Core::new().unwrap();
+    let handle = core.handle();
+    let client = Client::configure()
+        .connector(HttpsConnector::new(4, &handle).unwrap())
+        .build(&handle);
+    // hyper requires a 'static service, the client and config live as long as the process anyway
+    let client: &'static HttpsClient = Box::leak(Box::new(client));
+    let config: &'static Config = Box::leak(Box::new(config));
+    let exporter = Exporter {
+        plex: Plex::new(client, config.token.clone()),
+        client,
+        config,
+    };
+
+    // connections are served one after another, scrapes are rare enough for that
+    let serve = Http::new()
+        .keep_alive(false)
+        .serve_addr_handle(&config.listen, &handle, move || {
+            Ok::<_, io::Error>(exporter.clone())
+        })
+        .expect("Failed to bind the metrics address");
+    info!("Serving metrics on http://{}{}", config.listen, METRICS_PATH);
+    core.run(serve.for_each(|connection| connection.then(|_| Ok(()))))
+        .unwrap();
+}
@@ -0,0 +1,88 @@
+use std::fmt::Write;
+
+/// A value of a metric together with its labels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub labels: Vec<(&'static str, String)>,
+    pub value: f64,
+}
+
+impl Sample {
+    pub fn new(labels: Vec<(&'static str, String)>, value: f64) -> Self {
+        Sample { labels, value }
+    }
+
+    pub fn value(value: f64) -> Self {
+        Sample::new(Vec::new(), value)
+    }
+}
+
+/// Collects metrics in the prometheus text exposition format
+#[derive(Debug, Default)]
+pub struct Metrics {
+    out: String,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, samples: Vec<Sample>) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} gauge", name);
+        for sample in samples {
+            let labels = sample
+                .labels
+                .iter()
+                .map(|&(ref k, ref v)| format!("{}=\"{}\"", k, escape(v)))
+                .collect::<Vec<_>>();
+            match labels.is_empty() {
+                true => writeln!(self.out, "{} {}", name, sample.value),
+                _ => writeln!(self.out, "{}{{{}}} {}", name, labels.join(","), sample.value),
+            }.ok();
+        }
+    }
+
+    pub fn render(self) -> String {
+        self.out
+    }
+}
+
+/// label values must not break out of their quotes
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_gauges() {
+        let mut metrics = Metrics::new();
+        metrics.gauge("plex_up", "Whether the server could be scraped", vec![Sample::value(1.0)]);
+        metrics.gauge(
+            "plex_library_section_items",
+            "Number of items in a library section",
+            vec![
+                Sample::new(vec![("section", "Movies".to_string()), ("type", "movie".to_string())], 2.0),
+                Sample::new(vec![("section", "Say \"hi\"".to_string()), ("type", "show".to_string())], 10.0),
+            ],
+        );
+        assert_eq!(
+            metrics.render(),
+            r#"# HELP plex_up Whether the server could be scraped
+# TYPE plex_up gauge
+plex_up 1
+# HELP plex_library_section_items Number of items in a library section
+# TYPE plex_library_section_items gauge
+plex_library_section_items{section="Movies",type="movie"} 2
+plex_library_section_items{section="Say \"hi\"",type="show"} 10
+"#
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use hyper::client::Request;
+use hyper::StatusCode;
+use errors::APIError;
+use http::headers::{XPlexContainerSize, XPlexContainerStart};
+use regex::Regex;
+use serde_json;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Replaces tokens in recorded fixtures
+pub const SCRUBBED: &'static str = "REDACTED";
+
+/// How the `PlexClient` executes its requests
+#[derive(Debug, Clone)]
+pub enum Transport {
+    /// send every request to the server
+    Network,
+    /// send every request to the server and write the response to a fixture directory
+    Record(Rc<FixtureRecorder>),
+    /// answer every request from a fixture directory without touching the network
+    Replay(Rc<FixtureReplay>),
+}
+
+impl Transport {
+    /// Records into `dir`, the directory gets created if it does not exist yet
+    pub fn record<P: AsRef<Path>>(dir: P) -> io::Result<Transport> {
+        Ok(Transport::Record(Rc::new(FixtureRecorder::new(dir)?)))
+    }
+
+    /// Replays all fixtures that were recorded into `dir`
+    pub fn replay<P: AsRef<Path>>(dir: P) -> io::Result<Transport> {
+        Ok(Transport::Replay(Rc::new(FixtureReplay::from_dir(dir)?)))
+    }
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Transport::Network
+    }
+}
+
+/// A recorded request/response pair
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Fixture {
+    pub key: String,
+    pub status: u16,
+    pub body: String,
+}
+
+impl Fixture {
+    pub fn status(&self) -> StatusCode {
+        StatusCode::try_from(self.status).unwrap_or(StatusCode::InternalServerError)
+    }
+}
+
+/// The key a request is recorded and replayed under.
+///
+/// Consists of the method, the path and the query without the token and the paging headers,
+/// the host is left out so fixtures can be replayed against any server.
+pub fn request_key(request: &Request) -> String {
+    let uri = request.uri();
+    let query = uri.query()
+        .map(|q| {
+            q.split('&')
+                .filter(|p| !p.is_empty() && !p.starts_with("X-Plex-Token="))
+                .collect::<Vec<_>>()
+                .join("&")
+        })
+        .unwrap_or_default();
+    let mut key = format!("{} {}", request.method(), uri.path());
+    if !query.is_empty() {
+        key.push('?');
+        key.push_str(query.as_str());
+    }
+    let headers = request.headers();
+    if let Some(start) = headers.get::<XPlexContainerStart>() {
+        key.push_str(format!(" start={}", start.0).as_str());
+    }
+    if let Some(size) = headers.get::<XPlexContainerSize>() {
+        key.push_str(format!(" size={}", size.0).as_str());
+    }
+    key
+}
+
+/// file name of the fixture for the key, every byte that is not an ascii letter or digit
+/// gets escaped as `_` and its hex value so distinct keys never share a file
+fn file_name(key: &str) -> String {
+    let name = key.bytes()
+        .map(|b| match b.is_ascii_alphanumeric() {
+            true => (b as char).to_string(),
+            _ => format!("_{:02X}", b),
+        })
+        .collect::<String>();
+    format!("{}.json", name)
+}
+
+/// Removes the token and every token like attribute from a response body
+pub fn scrub(body: &str, token: &str) -> String {
+    lazy_static! {
+        static ref TOKEN_ATTR: Regex =
+            Regex::new(r#"(?P<attr>(authToken|authenticationToken|accessToken|token)=")[^"]*""#)
+                .unwrap();
+        static ref TOKEN_TAG: Regex =
+            Regex::new(r"<authentication-token>[^<]*</authentication-token>").unwrap();
+    }
+    let body = match token.is_empty() {
+        true => body.to_string(),
+        _ => body.replace(token, SCRUBBED),
+    };
+    let body = TOKEN_ATTR.replace_all(body.as_str(), format!("${{attr}}{}\"", SCRUBBED).as_str());
+    TOKEN_TAG
+        .replace_all(
+            &body,
+            format!("<authentication-token>{}</authentication-token>", SCRUBBED).as_str(),
+        )
+        .into_owned()
+}
+
+/// Writes every request/response pair into a fixture directory
+#[derive(Debug)]
+pub struct FixtureRecorder {
+    dir: PathBuf,
+}
+
+impl FixtureRecorder {
+    pub fn new<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(FixtureRecorder {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    pub fn record(
+        &self,
+        key: String,
+        status: StatusCode,
+        body: &str,
+        token: &str,
+    ) -> Result<(), APIError> {
+        let path = self.dir.join(file_name(key.as_str()));
+        let fixture = Fixture {
+            key,
+            status: status.as_u16(),
+            body: scrub(body, token),
+        };
+        let json = serde_json::to_string_pretty(&fixture)
+            .map_err(|e| APIError::ParseError(e.to_string()))?;
+        File::create(path)?.write_all(json.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Serves recorded fixtures, requests without a fixture are rejected
+#[derive(Debug, Default)]
+pub struct FixtureReplay {
+    fixtures: HashMap<String, Fixture>,
+}
+
+impl FixtureReplay {
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let mut fixtures = HashMap::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map(|e| e != "json").unwrap_or(true) {
+                continue;
+            }
+            let mut content = String::new();
+            File::open(&path)?.read_to_string(&mut content)?;
+            let fixture: Fixture = serde_json::from_str(content.as_str())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            fixtures.insert(fixture.key.clone(), fixture);
+        }
+        Ok(FixtureReplay { fixtures })
+    }
+
+    pub fn get(&self, key: &str) -> Result<&Fixture, APIError> {
+        self.fixtures
+            .get(key)
+            .ok_or_else(|| APIError::UnmatchedRequest(key.to_string()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.fixtures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fixtures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{Method, Uri};
+    use std::str::FromStr;
+
+    #[test]
+    fn request_key_without_token() {
+        let uri = Uri::from_str(
+            "http://127.0.0.1:32400/library/sections/1/all?X-Plex-Token=secret&year=2017",
+        ).unwrap();
+        let mut request = Request::new(Method::Get, uri);
+        assert_eq!(request_key(&request), "GET /library/sections/1/all?year=2017");
+
+        request
+            .headers_mut()
+            .set(XPlexContainerStart("100".to_string()));
+        request
+            .headers_mut()
+            .set(XPlexContainerSize("100".to_string()));
+        assert_eq!(
+            request_key(&request),
+            "GET /library/sections/1/all?year=2017 start=100 size=100"
+        );
+    }
+
+    #[test]
+    fn fixture_file_name() {
+        assert_eq!(
+            file_name("GET /library/sections/1/all?year=2017"),
+            "GET_20_2Flibrary_2Fsections_2F1_2Fall_3Fyear_3D2017.json"
+        );
+        assert_ne!(
+            file_name("GET /library/sections/1/all?year=2017"),
+            file_name("GET /library/sections/1/all/year/2017")
+        );
+    }
+
+    #[test]
+    fn scrub_tokens() {
+        let body = r##"<user authToken="secret" authenticationToken="secret" id="1">
+<Device accessToken="other" token="another" name="secret-server"/>
+<authentication-token>secret</authentication-token></user>"##;
+        let scrubbed = scrub(body, "secret");
+        assert!(!scrubbed.contains("secret"));
+        assert!(!scrubbed.contains("other"));
+        assert!(!scrubbed.contains("another"));
+        assert!(scrubbed.contains(r#"accessToken="REDACTED""#));
+    }
+}
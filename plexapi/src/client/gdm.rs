@@ -0,0 +1,284 @@
+//! Discovery of servers and players on the local network through plex's GDM ("good day mate")
+//! protocol, works without access to plex.tv.
+//!
+//! A `M-SEARCH` datagram is sent to the GDM port, every server or player that hears it answers
+//! with a small http like header block that describes it.
+
+use errors::APIError;
+use futures::{Future, Sink, Stream};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str;
+use std::time::Duration;
+use tokio_core::net::{UdpCodec, UdpSocket};
+use tokio_core::reactor::{Handle, Timeout};
+use types::device::Connection;
+
+pub const M_SEARCH: &'static str = "M-SEARCH * HTTP/1.0\r\n\r\n";
+pub const SERVER_PORT: u16 = 32414;
+pub const PLAYER_PORT: u16 = 32412;
+
+/// What to look for on the network
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GdmTarget {
+    Servers,
+    Players,
+}
+
+impl GdmTarget {
+    /// Servers listen on a multicast group, players only for broadcasts
+    pub fn destination(&self) -> SocketAddr {
+        match *self {
+            GdmTarget::Servers => {
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 0, 0, 250)), SERVER_PORT)
+            }
+            GdmTarget::Players => SocketAddr::new(IpAddr::V4(Ipv4Addr::new(255, 255, 255, 255)), PLAYER_PORT),
+        }
+    }
+}
+
+/// The answer of a server or player to a search
+#[derive(Debug, PartialEq, Clone)]
+pub struct GdmResponse {
+    /// where the answer came from
+    pub source: SocketAddr,
+    /// header names are lowercased
+    pub headers: HashMap<String, String>,
+}
+
+impl GdmResponse {
+    /// Parses a datagram, `None` if it is not a successful GDM answer
+    pub fn parse(source: SocketAddr, datagram: &[u8]) -> Option<GdmResponse> {
+        let text = str::from_utf8(datagram).ok()?;
+        let mut lines = text.lines();
+        if !lines.next()?.contains("200 OK") {
+            return None;
+        }
+        let headers = lines
+            .filter_map(|line| {
+                let mut split = line.splitn(2, ':');
+                let name = split.next()?.trim().to_lowercase();
+                let value = split.next()?.trim().to_string();
+                Some((name, value))
+            })
+            .collect();
+        Some(GdmResponse { source, headers })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|v| v.as_str())
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.header("name")
+    }
+
+    /// `plex/media-server` or `plex/media-player`
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    pub fn resource_identifier(&self) -> Option<&str> {
+        self.header("resource-identifier")
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.header("version")
+    }
+
+    /// The port the server or player accepts http requests on
+    pub fn port(&self) -> Option<u16> {
+        self.header("port").and_then(|p| p.parse().ok())
+    }
+
+    pub fn is_server(&self) -> bool {
+        self.content_type() == Some("plex/media-server")
+    }
+
+    pub fn is_player(&self) -> bool {
+        self.content_type() == Some("plex/media-player")
+    }
+
+    /// The http address, eg. for `Plex::connect`
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.port().map(|port| SocketAddr::new(self.source.ip(), port))
+    }
+
+    pub fn connection(&self) -> Option<Connection> {
+        self.address().map(Connection::from_endoint)
+    }
+}
+
+struct GdmCodec;
+
+impl UdpCodec for GdmCodec {
+    type In = Option<GdmResponse>;
+    type Out = SocketAddr;
+
+    fn decode(&mut self, src: &SocketAddr, buf: &[u8]) -> io::Result<Self::In> {
+        Ok(GdmResponse::parse(*src, buf))
+    }
+
+    fn encode(&mut self, dest: SocketAddr, buf: &mut Vec<u8>) -> SocketAddr {
+        buf.extend_from_slice(M_SEARCH.as_bytes());
+        dest
+    }
+}
+
+enum Event {
+    Response(Option<GdmResponse>),
+    Timeout,
+}
+
+/// Searches the local network, answers are collected until the timeout passes
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    destination: SocketAddr,
+    timeout: Duration,
+}
+
+impl Discovery {
+    pub fn new(target: GdmTarget) -> Self {
+        Discovery::with_destination(target.destination())
+    }
+
+    /// Sends the search to a specific address instead of the GDM multicast or broadcast address
+    pub fn with_destination(destination: SocketAddr) -> Self {
+        Discovery {
+            destination,
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Every server or player that answered, once per resource identifier. Answers without
+    /// one are told apart by the address they came from.
+    pub fn discover(&self, handle: &Handle) -> Box<Future<Item = Vec<GdmResponse>, Error = APIError>> {
+        let socket = match self.bind(handle) {
+            Ok(socket) => socket,
+            Err(e) => return Box::new(::futures::future::err(APIError::from(e))),
+        };
+        let timeout = match Timeout::new(self.timeout, handle) {
+            Ok(timeout) => timeout,
+            Err(e) => return Box::new(::futures::future::err(APIError::from(e))),
+        };
+        let search = socket
+            .framed(GdmCodec)
+            .send(self.destination)
+            .and_then(move |framed| {
+                framed
+                    .map(Event::Response)
+                    .select(timeout.into_stream().map(|_| Event::Timeout))
+                    .take_while(|event| match *event {
+                        Event::Timeout => Ok(false),
+                        _ => Ok(true),
+                    })
+                    .filter_map(|event| match event {
+                        Event::Response(response) => response,
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .map(dedup)
+            .map_err(APIError::from);
+        Box::new(search)
+    }
+
+    fn bind(&self, handle: &Handle) -> io::Result<UdpSocket> {
+        let local = match self.destination {
+            SocketAddr::V4(_) => "0.0.0.0:0",
+            _ => "[::]:0",
+        };
+        let socket = UdpSocket::bind(&local.parse().unwrap(), handle)?;
+        if let IpAddr::V4(ip) = self.destination.ip() {
+            if ip.is_broadcast() {
+                socket.set_broadcast(true)?;
+            }
+        }
+        Ok(socket)
+    }
+}
+
+/// Servers answer once per network interface
+fn dedup(responses: Vec<GdmResponse>) -> Vec<GdmResponse> {
+    let mut seen = HashSet::new();
+    responses
+        .into_iter()
+        .filter(|r| {
+            let key = match r.resource_identifier() {
+                Some(id) => (Some(id.to_string()), None),
+                None => (None, Some(r.source)),
+            };
+            seen.insert(key)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_gdm_response() {
+        let datagram = "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n\
+                        Resource-Identifier: 4c6a4aa1c1e2b0ab0ad0d4a1a8e3e07a1ae7f0d0\r\n\
+                        Name: Cloud\r\nPort: 32400\r\nUpdated-At: 1511736000\r\n\
+                        Version: 1.13.5.5291-6fa5e50a8\r\n\r\n";
+        let source = "192.168.1.10:32414".parse().unwrap();
+        let response = GdmResponse::parse(source, datagram.as_bytes()).unwrap();
+        assert!(response.is_server());
+        assert_eq!(response.name(), Some("Cloud"));
+        assert_eq!(response.version(), Some("1.13.5.5291-6fa5e50a8"));
+        assert_eq!(
+            response.address(),
+            Some("192.168.1.10:32400".parse().unwrap())
+        );
+        assert_eq!(
+            response.connection().unwrap().endpoint(),
+            "http://192.168.1.10:32400"
+        );
+
+        assert!(GdmResponse::parse(source, M_SEARCH.as_bytes()).is_none());
+        assert!(GdmResponse::parse(source, &[0xff, 0xfe]).is_none());
+    }
+
+    #[test]
+    fn dedup_responses() {
+        let server = |source: &str, id: &str| {
+            let datagram = format!(
+                "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n{}Port: 32400\r\n\r\n",
+                id
+            );
+            GdmResponse::parse(source.parse().unwrap(), datagram.as_bytes()).unwrap()
+        };
+        let responses = dedup(vec![
+            server("192.168.1.10:32414", "Resource-Identifier: abc\r\n"),
+            server("10.0.0.10:32414", "Resource-Identifier: abc\r\n"),
+            server("192.168.1.11:32414", ""),
+            server("192.168.1.12:32414", ""),
+            server("192.168.1.12:32414", ""),
+        ]);
+        let sources = responses
+            .iter()
+            .map(|r| r.source.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            vec!["192.168.1.10:32414", "192.168.1.11:32414", "192.168.1.12:32414"]
+        );
+    }
+
+    #[test]
+    fn players_destination() {
+        let destination = GdmTarget::Players.destination();
+        assert_eq!(destination, "255.255.255.255:32412".parse().unwrap());
+        assert_eq!(
+            GdmTarget::Servers.destination(),
+            "239.0.0.250:32414".parse().unwrap()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use hyper::client::{Client, FutureResponse, HttpConnector, Request};
-use hyper::{Body, Headers, Method, Uri};
+use hyper::{Body, Headers, Method, StatusCode, Uri};
 use hyper_tls::HttpsConnector;
 use futures::{future, Future, Stream};
 use serde::Deserialize;
@@ -16,6 +16,11 @@ use std::rc::Rc;
 use regex::Regex;
 use std::net::SocketAddr;
 
+pub mod fixture;
+pub mod gdm;
+
+use self::fixture::{request_key, Transport};
+
 #[macro_export]
 macro_rules! plex_client_wrapper {
 
@@ -42,6 +47,7 @@ pub struct PlexClient<'a> {
     pub client: &'a Client<HttpsConnector<HttpConnector>, Body>,
     pub headers: Headers,
     token: PlexToken,
+    transport: Transport,
 }
 
 /// plex does not escape chars:
@@ -61,16 +67,33 @@ impl<'a> PlexClient<'a> {
             client,
             headers,
             token,
+            transport: Transport::Network,
         }
     }
 
+    /// Switches between sending requests to the server, recording or replaying them
+    pub fn set_transport(&mut self, transport: Transport) {
+        self.transport = transport;
+    }
+
+    #[inline]
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    /// A request to `dest` that carries the plex headers
+    pub fn request(&self, method: Method, dest: &str) -> Request {
+        let url = Uri::from_str(dest).unwrap();
+        let mut request = Request::new(method, url);
+        request.headers_mut().extend(self.headers.iter());
+        request
+    }
+
     pub fn get_xml<'de, T: Deserialize<'de>>(
         &self,
         dest: &str,
     ) -> impl Future<Item = T, Error = APIError> {
-        let url = Uri::from_str(dest).unwrap();
-        let mut request = Request::new(Method::Get, url);
-        request.headers_mut().extend(self.headers.iter());
+        let request = self.request(Method::Get, dest);
         self.submit_request(request)
     }
 
@@ -80,49 +103,106 @@ impl<'a> PlexClient<'a> {
         start: usize,
         max: usize,
     ) -> impl Future<Item = T, Error = APIError> {
-        let url = Uri::from_str(dest).unwrap();
-        let mut request = Request::new(Method::Get, url);
-        request.headers_mut().extend(self.headers.iter());
+        let mut request = self.request(Method::Get, dest);
         request
             .headers_mut()
             .set(XPlexContainerStart(start.to_string()));
         request
             .headers_mut()
             .set(XPlexContainerSize(max.to_string()));
-        Self::from_xml_response(self.client.request(request))
+        self.submit_request(request)
+    }
+
+    /// Sends a request whose response body is of no interest, eg. a `PUT` or `DELETE`,
+    /// fails with `APIError::HttpError` if the server does not answer with success
+    pub fn send(&self, method: Method, dest: &str) -> impl Future<Item = (), Error = APIError> {
+        let request = self.request(method, dest);
+        self.send_request(request)
     }
 
-    fn submit_request<'de, T: Deserialize<'de>>(
+    /// Like `send`, for requests that need more than the plex headers
+    pub fn send_request(&self, request: Request) -> impl Future<Item = (), Error = APIError> {
+        self.execute(request)
+            .and_then(|(status, _)| match status.is_success() {
+                true => Ok(()),
+                _ => Err(APIError::HttpError(status)),
+            })
+    }
+
+    /// Like `get_xml`, for requests that need more than the plex headers
+    pub fn submit_request<'de, T: Deserialize<'de>>(
         &self,
         request: Request,
     ) -> impl Future<Item = T, Error = APIError> {
-        Self::from_xml_response(self.client.request(request))
+        self.execute(request)
+            .and_then(|(_, s)| Self::deserialize_xml(s))
+    }
+
+    /// Executes the request with the configured transport and returns the response status and body
+    fn execute(&self, request: Request) -> Box<Future<Item = (StatusCode, String), Error = APIError>> {
+        match self.transport {
+            Transport::Network => Box::new(
+                self.client
+                    .request(request)
+                    .map_err(APIError::from)
+                    .and_then(|res| {
+                        let status = res.status();
+                        Self::read_body(res.body()).map(move |body| (status, body))
+                    }),
+            ),
+            Transport::Record(ref recorder) => {
+                let key = request_key(&request);
+                let recorder = Rc::clone(recorder);
+                let token = self.token.clone();
+                Box::new(
+                    self.client
+                        .request(request)
+                        .map_err(APIError::from)
+                        .and_then(move |res| {
+                            let status = res.status();
+                            Self::read_body(res.body()).and_then(move |body| {
+                                recorder.record(key, status, body.as_str(), token.as_str())?;
+                                Ok((status, body))
+                            })
+                        }),
+                )
+            }
+            Transport::Replay(ref replay) => Box::new(future::result(
+                replay
+                    .get(request_key(&request).as_str())
+                    .map(|fixture| (fixture.status(), fixture.body.clone())),
+            )),
+        }
     }
 
     pub fn from_xml_response<'de, T: Deserialize<'de>>(
         fut_response: FutureResponse,
     ) -> impl Future<Item = T, Error = APIError> {
+        Self::read_response(fut_response).and_then(|s| Self::deserialize_xml(s))
+    }
+
+    fn read_response(fut_response: FutureResponse) -> impl Future<Item = String, Error = APIError> {
         fut_response
-            .map_err(|_| ())
-            .and_then(|res| {
-                let body = res.body()
-                    .map_err(|_| ())
-                    .fold(vec![], |mut acc, chunk| {
-                        acc.extend_from_slice(&chunk);
-                        Ok(acc)
-                    })
-                    .and_then(|v| String::from_utf8(v).map_err(|_| ()))
-                    .and_then(|s| {
-                        // escaped the & char which may break deserialization
-                        let escaped = s.replace("&", "&amp;");
-                        //                        println!("{}", escaped);
-                        deserialize::<_, T>(escaped.as_bytes()).map_err(|_| ())
-                    });
-                body
+            .map_err(|_| APIError::ReadError)
+            .and_then(|res| Self::read_body(res.body()))
+    }
+
+    fn read_body(body: Body) -> impl Future<Item = String, Error = APIError> {
+        body.map_err(|_| ())
+            .fold(vec![], |mut acc, chunk| {
+                acc.extend_from_slice(&chunk);
+                Ok(acc)
             })
+            .and_then(|v| String::from_utf8(v).map_err(|_| ()))
             .map_err(|_| APIError::ReadError)
     }
 
+    pub fn deserialize_xml<'de, T: Deserialize<'de>>(s: String) -> Result<T, APIError> {
+        // escaped the & char which may break deserialization
+        let escaped = s.replace("&", "&amp;");
+        deserialize::<_, T>(escaped.as_bytes()).map_err(|_| APIError::ReadError)
+    }
+
     pub fn escape_xml(s: &mut String) {
         lazy_static! {
             static ref RE: Regex = Regex::new(r"&((quot|apos|lt|gt|amp);)?").unwrap();
@@ -135,27 +215,21 @@ impl<'a> PlexClient<'a> {
         }
     }
 
+    /// The response body as is, fails with `APIError::HttpError` if the server does not
+    /// answer with success
+    pub fn get_text(&self, dest: &str) -> impl Future<Item = String, Error = APIError> {
+        let request = self.request(Method::Get, dest);
+        self.execute(request)
+            .and_then(|(status, body)| match status.is_success() {
+                true => Ok(body),
+                _ => Err(APIError::HttpError(status)),
+            })
+    }
+
     /// for dev purposes to get the response as string
     pub fn text_response(&self, dest: &str) -> impl Future<Item = String, Error = APIError> {
-        let url = Uri::from_str(dest).unwrap();
-        let mut request = Request::new(Method::Get, url);
-        request.headers_mut().extend(self.headers.iter());
-
-        self.client
-            .request(request)
-            .map_err(|_| ())
-            .and_then(|res| {
-                let body = res.body()
-                    .map_err(|_| ())
-                    .fold(vec![], |mut acc, chunk| {
-                        acc.extend_from_slice(&chunk);
-                        Ok(acc)
-                    })
-                    .and_then(|v| String::from_utf8(v).map_err(|_| ()));
-
-                body
-            })
-            .map_err(|_| APIError::ReadError)
+        let request = self.request(Method::Get, dest);
+        self.execute(request).map(|(_, body)| body)
     }
 
     #[inline]
@@ -177,17 +251,34 @@ impl<'a> PlexTokenProvider for PlexClient<'a> {
 #[derive(Debug, Clone)]
 pub struct Plex<'a> {
     client: Rc<PlexClient<'a>>,
+    devices_url: String,
 }
 
 impl<'a> Plex<'a> {
     pub fn new(c: &'a Client<HttpsConnector<HttpConnector>, Body>, token: PlexToken) -> Self {
-        let client = Rc::new(PlexClient::new(c, token));
-        Plex { client }
+        Plex::with_devices_url(c, token, DEVICES)
+    }
+
+    /// Uses a different endpoint than plex.tv to look up the devices of the account
+    pub fn with_devices_url(
+        c: &'a Client<HttpsConnector<HttpConnector>, Body>,
+        token: PlexToken,
+        devices_url: &str,
+    ) -> Self {
+        Plex::from_client(PlexClient::new(c, token), devices_url)
+    }
+
+    /// Uses an already configured client, eg. one that records or replays its requests
+    pub fn from_client(client: PlexClient<'a>, devices_url: &str) -> Self {
+        Plex {
+            client: Rc::new(client),
+            devices_url: devices_url.to_string(),
+        }
     }
 
     pub fn devices(&self) -> impl Future<Item = Vec<PlexDevice<'a>>, Error = APIError> {
         let client = Rc::clone(&self.client);
-        client.get_xml::<DeviceContainer>(DEVICES).map(move |d| {
+        client.get_xml::<DeviceContainer>(self.devices_url.as_str()).map(move |d| {
             d.devices
                 .into_iter()
                 .map(|m| PlexDevice::new(m, Rc::clone(&client)))
@@ -1,8 +1,12 @@
 use hyper;
 use hyper::StatusCode;
 use serde_xml_rs;
+use websocket::WebSocketError;
 use std::error::Error;
+use std::io;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use types::feature::Feature;
+use types::server::ServerVersion;
 
 #[derive(Debug)]
 pub enum APIError {
@@ -11,11 +15,38 @@ pub enum APIError {
     XmlError(serde_xml_rs::Error),
     HyperError(hyper::Error),
     ParseError(String),
+    IoError(io::Error),
+    WebSocketError(WebSocketError),
+    /// a replayed request for which no fixture was recorded
+    UnmatchedRequest(String),
+    /// a preference update that does not match what the server advertises
+    InvalidSetting(String),
+    /// a playback session without the id the server needs to terminate it
+    MissingSessionId(String),
+    /// an argument outside of the values the operation accepts
+    InvalidArgument(String),
+    /// the server is too old for the requested operation
+    UnsupportedVersion {
+        feature: Feature,
+        required: ServerVersion,
+        found: ServerVersion,
+    },
 }
 
 impl Display for APIError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "Error! {}. ({:?})", self.description(), self)
+        match *self {
+            APIError::UnsupportedVersion {
+                ref feature,
+                ref required,
+                ref found,
+            } => write!(
+                f,
+                "Error! {} is unsupported by server version {}, requires at least {}.",
+                feature, found, required
+            ),
+            _ => write!(f, "Error! {}. ({:?})", self.description(), self),
+        }
     }
 }
 
@@ -28,6 +59,15 @@ impl Error for APIError {
             APIError::XmlError(_) => {
                 "The Xml sent by Plex did not match what the plexapi was expecting"
             }
+            APIError::IoError(_) => "An I/O error occurred",
+            APIError::WebSocketError(_) => "An error occurred on the websocket",
+            APIError::UnmatchedRequest(_) => "No fixture was recorded for the request",
+            APIError::InvalidSetting(_) => "The value is not valid for the server setting",
+            APIError::MissingSessionId(_) => "The playback session has no session id",
+            APIError::InvalidArgument(_) => "An argument is outside of the accepted values",
+            APIError::UnsupportedVersion { .. } => {
+                "The server version does not support the requested operation"
+            }
             _ => "This error should not have occurred. Please file a bug",
         }
     }
@@ -44,3 +84,15 @@ impl From<serde_xml_rs::Error> for APIError {
         APIError::XmlError(err)
     }
 }
+
+impl From<io::Error> for APIError {
+    fn from(err: io::Error) -> APIError {
+        APIError::IoError(err)
+    }
+}
+
+impl From<WebSocketError> for APIError {
+    fn from(err: WebSocketError) -> APIError {
+        APIError::WebSocketError(err)
+    }
+}
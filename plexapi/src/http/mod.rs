@@ -34,10 +34,13 @@ header! { (XPlexContainerStart, "X-Plex-Container-Start") => [String] }
 /// Authentication token
 header! { (XPlexToken, "X-Plex-Token") => [String] }
 
+/// Client identifier of the player a companion command is meant for
+header! { (XPlexTargetClientIdentifier, "X-Plex-Target-Client-Identifier") => [String] }
+
 pub mod headers {
     pub use super::{XPlexClientIdentifier, XPlexContainerSize, XPlexContainerStart, XPlexDevice,
-                    XPlexPlatform, XPlexPlatformVersion, XPlexProduct, XPlexProvides, XPlexToken,
-                    XPlexVersion};
+                    XPlexPlatform, XPlexPlatformVersion, XPlexProduct, XPlexProvides,
+                    XPlexTargetClientIdentifier, XPlexToken, XPlexVersion};
 }
 
 pub fn set_basic_plex_headers(headers: &mut Headers) {
@@ -18,12 +18,22 @@ extern crate tokio_core;
 extern crate tokio_service;
 extern crate uname;
 extern crate url;
+extern crate websocket;
 
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+extern crate serde_json;
 extern crate serde_xml_rs;
 
+/// Reads one of the xml fixtures the integration tests serve, so unit tests share them
+#[cfg(test)]
+macro_rules! fixture {
+    ($name:expr) => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/fixtures/", $name))
+    };
+}
+
 pub mod http;
 #[macro_use]
 pub mod client;
@@ -28,13 +28,33 @@ impl Login {
         &self,
         client: &'a Client<HttpsConnector<HttpConnector>, Body>,
     ) -> impl Future<Item = PlexToken, Error = APIError> {
-        let url = Uri::from_str(SIGNIN).unwrap();
+        self.sign_in(client, SIGNIN)
+    }
+
+    /// Signs in against the given sign in endpoint instead of plex.tv
+    pub fn sign_in<'a>(
+        &self,
+        client: &'a Client<HttpsConnector<HttpConnector>, Body>,
+        sign_in_url: &str,
+    ) -> impl Future<Item = PlexToken, Error = APIError> {
+        self.sign_in_with(&PlexClient::new(client, PlexToken::new()), sign_in_url)
+    }
+
+    /// Signs in with the transport of `client`, so the sign in gets recorded and replayed
+    /// like any other request, the token of `client` is not sent
+    pub fn sign_in_with<'a>(
+        &self,
+        client: &PlexClient<'a>,
+        sign_in_url: &str,
+    ) -> impl Future<Item = PlexToken, Error = APIError> {
+        let url = Uri::from_str(sign_in_url).unwrap();
         let mut request = Request::new(Method::Post, url);
         set_basic_plex_headers(request.headers_mut());
         let header: Authorization<Basic> = self.into();
         request.headers_mut().set(header);
-        let resp = client.request(request);
-        PlexClient::from_xml_response::<User>(resp).map(|u| u.auth_token)
+        client
+            .submit_request::<User>(request)
+            .map(|u| u.auth_token)
     }
 }
 
@@ -0,0 +1,74 @@
+/// What an activity is working on, eg. the library section that is scanned
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivityContext {
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: String,
+}
+
+/// A long running task of the server, eg. a library scan or a media analysis
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Activity {
+    pub uuid: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub cancellable: String,
+    #[serde(rename = "userID")]
+    pub user_id: String,
+    pub title: String,
+    pub subtitle: String,
+    pub progress: String,
+    #[serde(rename = "Context")]
+    pub context: Option<ActivityContext>,
+}
+
+impl Activity {
+    pub fn is_cancellable(&self) -> bool {
+        "1".eq(&self.cancellable)
+    }
+
+    /// progress in percent
+    pub fn progress(&self) -> Option<u32> {
+        self.progress.parse().ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivityContainer {
+    pub size: String,
+    #[serde(rename = "Activity", default)]
+    pub activities: Vec<Activity>,
+}
+
+impl ActivityContainer {
+    pub const PATH: &'static str = "/activities";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn activities_deserialize() {
+        let xml = fixture!("activities.xml");
+        let container: Result<ActivityContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let activities = container.unwrap().activities;
+        assert_eq!(activities.len(), 1);
+        assert!(activities[0].is_cancellable());
+        assert_eq!(activities[0].progress(), Some(42));
+        assert_eq!(activities[0].context.as_ref().unwrap().library_section_id, "1");
+
+        let thumbnails = Activity {
+            cancellable: "0".to_string(),
+            progress: "7".to_string(),
+            ..Activity::default()
+        };
+        assert!(!thumbnails.is_cancellable());
+        assert_eq!(thumbnails.progress(), Some(7));
+        assert!(thumbnails.context.is_none());
+    }
+}
@@ -0,0 +1,95 @@
+use client::PlexClient;
+use types::media::audio::Track;
+use types::media::video::Video;
+use types::server::Directory;
+
+/// Any container of the server, as far as it is understood without a dedicated model
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BrowseContainer {
+    pub size: String,
+    pub title1: String,
+    pub title2: String,
+    pub identifier: String,
+    pub content: String,
+    pub view_group: String,
+    #[serde(rename = "Directory", default)]
+    pub directories: Vec<Directory>,
+    #[serde(rename = "Video", default)]
+    pub videos: Vec<Video>,
+    #[serde(rename = "Track", default)]
+    pub tracks: Vec<Track>,
+}
+
+impl BrowseContainer {
+    pub fn has_items(&self) -> bool {
+        !self.videos.is_empty() || !self.tracks.is_empty()
+    }
+}
+
+/// What a browsed path turned out to be
+#[derive(Debug, PartialEq, Clone)]
+pub enum BrowseNode {
+    /// only entries that lead further
+    Directories(Vec<Directory>),
+    /// videos or tracks, possibly next to directories
+    Items(BrowseContainer),
+    /// the body of a container the browser does not understand, or an empty one
+    Raw(String),
+}
+
+impl BrowseNode {
+    pub fn parse(body: String) -> BrowseNode {
+        match PlexClient::deserialize_xml::<BrowseContainer>(body.clone()) {
+            Ok(ref container) if container.has_items() => BrowseNode::Items(container.clone()),
+            Ok(ref container) if !container.directories.is_empty() => {
+                BrowseNode::Directories(container.directories.clone())
+            }
+            _ => BrowseNode::Raw(body),
+        }
+    }
+
+    pub fn directories(&self) -> &[Directory] {
+        match *self {
+            BrowseNode::Directories(ref directories) => directories.as_slice(),
+            BrowseNode::Items(ref container) => container.directories.as_slice(),
+            BrowseNode::Raw(_) => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nodes() {
+        let node = BrowseNode::parse(fixture!("library.xml").to_string());
+        assert_eq!(node.directories().len(), 3);
+        assert_eq!(node.directories()[0].path("/library"), "/library/sections");
+        assert_eq!(node.directories()[0].path("/library/"), "/library/sections");
+        assert!(!node.directories()[0].is_search());
+
+        let search = Directory {
+            key: "/hubs/search".to_string(),
+            search: "1".to_string(),
+            ..Directory::default()
+        };
+        assert_eq!(search.path("/library"), "/hubs/search");
+        assert!(search.is_search());
+
+        match BrowseNode::parse(fixture!("movies.xml").to_string()) {
+            BrowseNode::Items(ref container) => {
+                assert_eq!(container.videos[0].title, "First Movie");
+                assert_eq!(container.view_group, "movie");
+            }
+            ref other => panic!("unexpected node {:?}", other),
+        }
+
+        let xml = fixture!("devices.xml");
+        assert_eq!(
+            BrowseNode::parse(xml.to_string()),
+            BrowseNode::Raw(xml.to_string())
+        );
+    }
+}
@@ -0,0 +1,114 @@
+/// Scheduled maintenance tasks of the server
+#[derive(Debug, PartialEq, Clone)]
+pub enum ButlerTaskName {
+    BackupDatabase,
+    CleanOldBundles,
+    CleanOldCacheFiles,
+    DeepMediaAnalysis,
+    GenerateAutoTags,
+    OptimizeDatabase,
+    RefreshLibraries,
+    RefreshLocalMedia,
+    RefreshPeriodicMetadata,
+    UpgradeMediaAnalysis,
+    /// tasks that are not known to this crate yet
+    Other(String),
+}
+
+impl ButlerTaskName {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            ButlerTaskName::BackupDatabase => "BackupDatabase",
+            ButlerTaskName::CleanOldBundles => "CleanOldBundles",
+            ButlerTaskName::CleanOldCacheFiles => "CleanOldCacheFiles",
+            ButlerTaskName::DeepMediaAnalysis => "DeepMediaAnalysis",
+            ButlerTaskName::GenerateAutoTags => "GenerateAutoTags",
+            ButlerTaskName::OptimizeDatabase => "OptimizeDatabase",
+            ButlerTaskName::RefreshLibraries => "RefreshLibraries",
+            ButlerTaskName::RefreshLocalMedia => "RefreshLocalMedia",
+            ButlerTaskName::RefreshPeriodicMetadata => "RefreshPeriodicMetadata",
+            ButlerTaskName::UpgradeMediaAnalysis => "UpgradeMediaAnalysis",
+            ButlerTaskName::Other(ref name) => name.as_str(),
+        }
+    }
+
+    pub fn from_str(s: &str) -> ButlerTaskName {
+        match s {
+            "BackupDatabase" => ButlerTaskName::BackupDatabase,
+            "CleanOldBundles" => ButlerTaskName::CleanOldBundles,
+            "CleanOldCacheFiles" => ButlerTaskName::CleanOldCacheFiles,
+            "DeepMediaAnalysis" => ButlerTaskName::DeepMediaAnalysis,
+            "GenerateAutoTags" => ButlerTaskName::GenerateAutoTags,
+            "OptimizeDatabase" => ButlerTaskName::OptimizeDatabase,
+            "RefreshLibraries" => ButlerTaskName::RefreshLibraries,
+            "RefreshLocalMedia" => ButlerTaskName::RefreshLocalMedia,
+            "RefreshPeriodicMetadata" => ButlerTaskName::RefreshPeriodicMetadata,
+            "UpgradeMediaAnalysis" => ButlerTaskName::UpgradeMediaAnalysis,
+            _ => ButlerTaskName::Other(s.to_string()),
+        }
+    }
+}
+
+/// A maintenance task and its schedule
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ButlerTask {
+    pub name: String,
+    /// days between two runs
+    pub interval: String,
+    pub schedule_randomized: String,
+    pub enabled: String,
+    pub title: String,
+    pub description: String,
+}
+
+impl ButlerTask {
+    pub fn task(&self) -> ButlerTaskName {
+        ButlerTaskName::from_str(self.name.as_str())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        "1".eq(&self.enabled)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ButlerTaskContainer {
+    #[serde(rename = "ButlerTask", default)]
+    pub tasks: Vec<ButlerTask>,
+}
+
+impl ButlerTaskContainer {
+    pub const PATH: &'static str = "/butler";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn butler_tasks_deserialize() {
+        let xml = fixture!("butler.xml");
+        let container: Result<ButlerTaskContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let tasks = container.unwrap().tasks;
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].task(), ButlerTaskName::BackupDatabase);
+        assert_eq!(tasks[1].task(), ButlerTaskName::CleanOldBundles);
+        assert_eq!(tasks[2].task(), ButlerTaskName::OptimizeDatabase);
+        assert!(tasks.iter().all(|t| t.is_enabled()));
+
+        let task = ButlerTask {
+            name: "ReverseGeocode".to_string(),
+            enabled: "0".to_string(),
+            ..ButlerTask::default()
+        };
+        assert_eq!(
+            task.task(),
+            ButlerTaskName::Other("ReverseGeocode".to_string())
+        );
+        assert!(!task.is_enabled());
+    }
+}
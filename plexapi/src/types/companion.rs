@@ -0,0 +1,379 @@
+use client::PlexClient;
+use errors::APIError;
+use futures::{Async, Future, Poll, Stream};
+use http::headers::XPlexTargetClientIdentifier;
+use hyper::Method;
+use hyper::client::Request;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use tokio_core::reactor::{Handle, Timeout};
+use types::device::Connection;
+use types::server::PlexServer;
+use types::timeline::TimelineContainer;
+use types::PlexTokenProvider;
+use url::form_urlencoded::Serializer;
+
+/// Streams a player can switch between
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StreamType {
+    Audio,
+    Subtitle,
+}
+
+impl StreamType {
+    fn parameter(&self) -> &'static str {
+        match *self {
+            StreamType::Audio => "audioStreamID",
+            StreamType::Subtitle => "subtitleStreamID",
+        }
+    }
+}
+
+/// A player a server knows about, eg. because it is on the same network
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerClient {
+    pub name: String,
+    pub host: String,
+    pub address: String,
+    pub port: String,
+    pub machine_identifier: String,
+    pub version: String,
+    pub protocol: String,
+    pub product: String,
+    pub device_class: String,
+    pub protocol_version: String,
+    /// comma separated, eg. `timeline,playback,navigation`
+    pub protocol_capabilities: String,
+}
+
+impl ServerClient {
+    pub fn capabilities(&self) -> Vec<&str> {
+        self.protocol_capabilities
+            .split(',')
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities().contains(&capability)
+    }
+
+    /// Whether the player can be remote controlled
+    pub fn is_controllable(&self) -> bool {
+        self.has_capability("playback")
+    }
+
+    /// The direct connection to the player, the server is not involved
+    pub fn connection(&self) -> Connection {
+        Connection::new(self.address.as_str(), self.port.as_str())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerClientContainer {
+    pub size: String,
+    #[serde(rename = "Server", default)]
+    pub clients: Vec<ServerClient>,
+}
+
+impl ServerClientContainer {
+    pub const PATH: &'static str = "/clients";
+}
+
+/// Remote control of a player through the companion protocol.
+///
+/// Commands are sent either to the player itself or to a server the player is connected to,
+/// the server passes them on to the player with the target client identifier.
+#[derive(Debug, Clone)]
+pub struct PlexPlayer<'a> {
+    client: Rc<PlexClient<'a>>,
+    conn: Connection,
+    machine_identifier: String,
+    /// players ignore commands whose id is not higher than the last one of the controller,
+    /// shared with the clones of the player
+    command_id: Rc<Cell<u64>>,
+}
+
+impl<'a> PlexPlayer<'a> {
+    pub const PLAYBACK: &'static str = "/player/playback";
+
+    pub fn new(client: Rc<PlexClient<'a>>, conn: Connection, machine_identifier: &str) -> Self {
+        PlexPlayer {
+            client,
+            conn,
+            machine_identifier: machine_identifier.to_string(),
+            command_id: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub fn machine_identifier(&self) -> &str {
+        self.machine_identifier.as_str()
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn play(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("play", vec![])
+    }
+
+    pub fn pause(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("pause", vec![])
+    }
+
+    pub fn stop(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("stop", vec![])
+    }
+
+    pub fn skip_next(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("skipNext", vec![])
+    }
+
+    pub fn skip_previous(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("skipPrevious", vec![])
+    }
+
+    pub fn step_forward(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("stepForward", vec![])
+    }
+
+    pub fn step_back(&self) -> impl Future<Item = (), Error = APIError> {
+        self.command("stepBack", vec![])
+    }
+
+    /// Jumps to the offset in milliseconds
+    pub fn seek_to(&self, offset: u64) -> impl Future<Item = (), Error = APIError> {
+        self.command("seekTo", vec![("offset", offset.to_string())])
+    }
+
+    /// Volume in percent, values above 100 are capped
+    pub fn set_volume(&self, volume: u8) -> impl Future<Item = (), Error = APIError> {
+        let volume = if volume > 100 { 100 } else { volume };
+        self.command("setParameters", vec![("volume", volume.to_string())])
+    }
+
+    /// Switches to the stream with the id, eg. the `id` of a `Stream` of the played media
+    pub fn select_stream(
+        &self,
+        stream_type: StreamType,
+        id: &str,
+    ) -> impl Future<Item = (), Error = APIError> {
+        self.command("setStreams", vec![(stream_type.parameter(), id.to_string())])
+    }
+
+    /// Plays the item with the key, eg. `/library/metadata/444`, from the server,
+    /// starting at the offset in milliseconds
+    pub fn play_media(
+        &self,
+        server: &PlexServer,
+        key: &str,
+        offset: u64,
+    ) -> impl Future<Item = (), Error = APIError> {
+        let conn = server.connection();
+        let protocol = conn.protocol()
+            .map(|p| p.as_str())
+            .unwrap_or("http")
+            .to_string();
+        self.command(
+            "playMedia",
+            vec![
+                ("key", key.to_string()),
+                ("containerKey", key.to_string()),
+                ("offset", offset.to_string()),
+                ("machineIdentifier", server.inner.machine_identifier().to_string()),
+                ("address", conn.address.clone().unwrap_or_default()),
+                ("port", conn.port.clone().unwrap_or_default()),
+                ("protocol", protocol),
+                ("token", self.client.token()),
+            ],
+        )
+    }
+
+    /// The current timelines of the player
+    pub fn timeline(&self) -> impl Future<Item = TimelineContainer, Error = APIError> {
+        let request = self.poll_request(false);
+        self.client.submit_request(request)
+    }
+
+    /// Subscribes to the timelines of the player, see `TimelineStream`
+    pub fn timelines(&self, handle: &Handle) -> TimelineStream<'a> {
+        TimelineStream::new(self.clone(), handle)
+    }
+
+    fn command(
+        &self,
+        command: &str,
+        params: Vec<(&str, String)>,
+    ) -> impl Future<Item = (), Error = APIError> {
+        let url = format!(
+            "{}{}/{}",
+            self.conn.endpoint(),
+            PlexPlayer::PLAYBACK,
+            command
+        );
+        let request = self.request(url.as_str(), &params);
+        self.client.send_request(request)
+    }
+
+    /// A poll of the timelines, waiting polls are answered once something changes
+    fn poll_request(&self, wait: bool) -> Request {
+        let url = format!("{}{}", self.conn.endpoint(), TimelineContainer::POLL);
+        let wait = match wait {
+            true => "1",
+            _ => "0",
+        };
+        self.request(url.as_str(), &[("wait", wait.to_string())])
+    }
+
+    fn request(&self, url: &str, params: &[(&str, String)]) -> Request {
+        let command_id = self.command_id.get() + 1;
+        self.command_id.set(command_id);
+        let url = format!("{}?{}", url, command_query(command_id, params));
+        let mut request = self.client.request(Method::Get, url.as_str());
+        request
+            .headers_mut()
+            .set(XPlexTargetClientIdentifier(self.machine_identifier.clone()));
+        request
+    }
+}
+
+enum StreamState<'a> {
+    Polling(Box<Future<Item = TimelineContainer, Error = APIError> + 'a>),
+    Waiting(Timeout),
+}
+
+enum Step {
+    Subscribe,
+    Timelines(TimelineContainer),
+    Resubscribe,
+}
+
+/// `Stream` of the timelines of a player.
+///
+/// The first poll subscribes and answers right away, the following ones wait until the player
+/// reports a change. Whenever a poll fails the stream waits for the resubscribe delay and
+/// subscribes again, so it only ends when it is dropped.
+pub struct TimelineStream<'a> {
+    player: PlexPlayer<'a>,
+    handle: Handle,
+    resubscribe_delay: Duration,
+    state: StreamState<'a>,
+}
+
+impl<'a> TimelineStream<'a> {
+    pub fn new(player: PlexPlayer<'a>, handle: &Handle) -> Self {
+        let state = TimelineStream::polling(&player, false);
+        TimelineStream {
+            player,
+            handle: handle.clone(),
+            resubscribe_delay: Duration::from_secs(5),
+            state,
+        }
+    }
+
+    pub fn set_resubscribe_delay(&mut self, delay: Duration) {
+        self.resubscribe_delay = delay;
+    }
+
+    pub fn player(&self) -> &PlexPlayer<'a> {
+        &self.player
+    }
+
+    fn polling(player: &PlexPlayer<'a>, wait: bool) -> StreamState<'a> {
+        let request = player.poll_request(wait);
+        StreamState::Polling(Box::new(player.client.submit_request(request)))
+    }
+}
+
+impl<'a> Stream for TimelineStream<'a> {
+    type Item = TimelineContainer;
+    type Error = APIError;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let step = match self.state {
+                StreamState::Polling(ref mut poll) => match poll.poll() {
+                    Ok(Async::Ready(timelines)) => Step::Timelines(timelines),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => {
+                        warn!("Failed to poll the timelines of the player: {:?}", e);
+                        Step::Resubscribe
+                    }
+                },
+                StreamState::Waiting(ref mut timeout) => match timeout.poll()? {
+                    Async::Ready(()) => Step::Subscribe,
+                    Async::NotReady => return Ok(Async::NotReady),
+                },
+            };
+            match step {
+                Step::Subscribe => self.state = TimelineStream::polling(&self.player, false),
+                Step::Timelines(timelines) => {
+                    self.state = TimelineStream::polling(&self.player, true);
+                    return Ok(Async::Ready(Some(timelines)));
+                }
+                Step::Resubscribe => {
+                    self.state =
+                        StreamState::Waiting(Timeout::new(self.resubscribe_delay, &self.handle)?)
+                }
+            }
+        }
+    }
+}
+
+/// The query of a command, the command id always comes first
+fn command_query(command_id: u64, params: &[(&str, String)]) -> String {
+    let mut query = Serializer::new(String::new());
+    query.append_pair("commandID", command_id.to_string().as_str());
+    for &(ref name, ref value) in params {
+        query.append_pair(name, value.as_str());
+    }
+    query.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn clients_deserialize() {
+        let xml = fixture!("clients.xml");
+        let container: Result<ServerClientContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let clients = container.unwrap().clients;
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].machine_identifier, "mockplayeridentifier");
+        assert!(clients[0].is_controllable());
+        assert!(clients[0].has_capability("timeline"));
+        assert_eq!(clients[0].capabilities().len(), 4);
+
+        let cast = ServerClient {
+            address: "192.168.1.21".to_string(),
+            port: "8009".to_string(),
+            ..ServerClient::default()
+        };
+        assert!(cast.capabilities().is_empty());
+        assert!(!cast.is_controllable());
+        assert_eq!(cast.connection().endpoint(), "http://192.168.1.21:8009");
+    }
+
+    #[test]
+    fn command_queries() {
+        assert_eq!(command_query(1, &[]), "commandID=1");
+        assert_eq!(
+            command_query(
+                7,
+                &[
+                    ("key", "/library/metadata/444".to_string()),
+                    ("offset", "60000".to_string()),
+                ]
+            ),
+            "commandID=7&key=%2Flibrary%2Fmetadata%2F444&offset=60000"
+        );
+        assert_eq!(StreamType::Subtitle.parameter(), "subtitleStreamID");
+    }
+}
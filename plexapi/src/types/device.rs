@@ -2,6 +2,7 @@ use client::PlexClient;
 use futures::{future, Future};
 use errors::APIError;
 use serde_xml_rs::Error;
+use types::companion::PlexPlayer;
 use types::server::{PlexServer, Server};
 use types::PlexToken;
 use std::rc::Rc;
@@ -18,16 +19,21 @@ impl<'a> PlexDevice<'a> {
         PlexDevice { inner, client }
     }
 
-    /// Connects to the device's connection, prefers local connections
-    pub fn connect(&self) -> impl Future<Item = PlexServer<'a>, Error = APIError> {
-        let con = match self.inner.connections.len() {
+    /// The connection to use for the device, local connections are preferred
+    pub fn preferred_connection(&self) -> Option<&Connection> {
+        match self.inner.connections.len() {
             0 => None,
             1 => self.inner.connections.first(),
             _ => match self.inner.connections.iter().find(|p| p.is_local()) {
                 Some(c) => Some(c),
                 _ => self.inner.connections.first(),
             },
-        };
+        }
+    }
+
+    /// Connects to the device's connection, prefers local connections
+    pub fn connect(&self) -> impl Future<Item = PlexServer<'a>, Error = APIError> {
+        let con = self.preferred_connection();
 
         // boxing necessary to unify return type...
         let res: Box<Future<Item = PlexServer, Error = APIError>> = match con {
@@ -48,6 +54,17 @@ impl<'a> PlexDevice<'a> {
         };
         res
     }
+
+    /// Remote control for a player device, `None` if the device has no connection
+    pub fn player(&self) -> Option<PlexPlayer<'a>> {
+        self.preferred_connection().map(|conn| {
+            PlexPlayer::new(
+                Rc::clone(&self.client),
+                conn.clone(),
+                self.inner.client_identifier.as_str(),
+            )
+        })
+    }
 }
 
 //pub fn select_device<'a, F: Future<Item=Vec<PlexDevice<'a>>, Error=APIError>>(f: F, name: &'a str)
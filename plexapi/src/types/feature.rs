@@ -0,0 +1,128 @@
+use errors::APIError;
+use std::fmt;
+use types::server::ServerVersion;
+
+/// Server operations that are not available on every server version
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Feature {
+    Library,
+    Sessions,
+    TerminateSession,
+    Notifications,
+    History,
+    Activities,
+    Butler,
+    Statistics,
+    TranscodeSessions,
+    Updater,
+    HubSearch,
+    Hubs,
+    Preferences,
+    Clients,
+}
+
+impl Feature {
+    /// The oldest server version that supports the feature.
+    ///
+    /// The versions follow the Plex Media Server release notes on forums.plex.tv, the endpoint
+    /// a feature covers is noted next to its version.
+    pub fn min_version(&self) -> ServerVersion {
+        match *self {
+            // /library
+            Feature::Library => ServerVersion::new(0, 9, 0, 0),
+            // /status/sessions
+            Feature::Sessions => ServerVersion::new(0, 9, 8, 0),
+            // /status/sessions/terminate
+            Feature::TerminateSession => ServerVersion::new(1, 4, 0, 0),
+            // /:/websockets/notifications
+            Feature::Notifications => ServerVersion::new(1, 3, 0, 0),
+            // /status/sessions/history/all
+            Feature::History => ServerVersion::new(1, 0, 0, 0),
+            // /activities
+            Feature::Activities => ServerVersion::new(1, 10, 0, 0),
+            // /butler
+            Feature::Butler => ServerVersion::new(1, 7, 0, 0),
+            // /statistics/bandwidth and /statistics/resources
+            Feature::Statistics => ServerVersion::new(1, 12, 0, 0),
+            // /transcode/sessions
+            Feature::TranscodeSessions => ServerVersion::new(1, 3, 0, 0),
+            // /updater/status
+            Feature::Updater => ServerVersion::new(1, 0, 0, 0),
+            // /hubs/search
+            Feature::HubSearch => ServerVersion::new(0, 9, 9, 0),
+            // /hubs and /hubs/sections
+            Feature::Hubs => ServerVersion::new(1, 0, 0, 0),
+            // /:/prefs
+            Feature::Preferences => ServerVersion::new(0, 9, 0, 0),
+            // /clients
+            Feature::Clients => ServerVersion::new(0, 9, 0, 0),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Feature::Library => "library",
+            Feature::Sessions => "sessions",
+            Feature::TerminateSession => "terminate session",
+            Feature::Notifications => "notifications",
+            Feature::History => "history",
+            Feature::Activities => "activities",
+            Feature::Butler => "butler",
+            Feature::Statistics => "statistics",
+            Feature::TranscodeSessions => "transcode sessions",
+            Feature::Updater => "updater",
+            Feature::HubSearch => "hub search",
+            Feature::Hubs => "hubs",
+            Feature::Preferences => "preferences",
+            Feature::Clients => "clients",
+        }
+    }
+
+    pub fn is_supported_by(&self, version: &ServerVersion) -> bool {
+        *version >= self.min_version()
+    }
+
+    /// Fails with `APIError::UnsupportedVersion` if the server version is too old.
+    ///
+    /// Versions that can not be parsed are let through, the server gets the final say.
+    pub fn require(&self, version: &str) -> Result<(), APIError> {
+        match version.parse::<ServerVersion>() {
+            Ok(ref v) if !self.is_supported_by(v) => Err(APIError::UnsupportedVersion {
+                feature: *self,
+                required: self.min_version(),
+                found: v.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn require_version() {
+        assert!(Feature::Library.require("1.7.2.3878-8088811b8").is_ok());
+        assert!(Feature::Statistics.require("1.12.0.4829-6de959918").is_ok());
+        assert!(Feature::Statistics.require("").is_ok());
+        match Feature::Statistics.require("1.7.2.3878-8088811b8") {
+            Err(APIError::UnsupportedVersion {
+                feature,
+                required,
+                found,
+            }) => {
+                assert_eq!(feature, Feature::Statistics);
+                assert_eq!(required, ServerVersion::new(1, 12, 0, 0));
+                assert_eq!(found.to_string(), "1.7.2.3878-8088811b8");
+            }
+            other => panic!("expected unsupported version, got {:?}", other),
+        }
+    }
+}
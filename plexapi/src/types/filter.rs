@@ -1,7 +1,12 @@
-use std::borrow::Cow;
+use url::form_urlencoded::byte_serialize;
 
-fn encode(v: &Vec<String>) -> Cow<[u8]> {
-    Cow::Owned(v.join(",").into_bytes())
+fn escape(v: &str) -> String {
+    byte_serialize(v.as_bytes()).collect()
+}
+
+/// Multiple values of a filter are separated by commas, the values themselves are escaped
+fn encode(v: &[String]) -> String {
+    v.iter().map(|s| escape(s)).collect::<Vec<_>>().join(",")
 }
 
 pub trait LibraryFilter {
@@ -16,7 +21,8 @@ pub enum MovieLibraryFilter {
     Year(Vec<u16>),
     Decade(Vec<u8>),
     Genre(Vec<String>),
-    ContentRating(u8),
+    /// eg. `PG-13`
+    ContentRating(String),
     Collection(Vec<String>),
     Director(Vec<String>),
     Actor(Vec<String>),
@@ -30,8 +36,8 @@ pub enum MovieLibraryFilter {
 impl LibraryFilter for MovieLibraryFilter {
     fn format(&self) -> String {
         match *self {
-            MovieLibraryFilter::Unwachted(v) => format!("unwatched={}", v),
-            MovieLibraryFilter::Duplicate(v) => format!("duplicate={}", v),
+            MovieLibraryFilter::Unwachted(v) => format!("unwatched={}", v as u8),
+            MovieLibraryFilter::Duplicate(v) => format!("duplicate={}", v as u8),
             MovieLibraryFilter::Year(ref v) => format!(
                 "year={}",
                 v.iter()
@@ -46,16 +52,16 @@ impl LibraryFilter for MovieLibraryFilter {
                     .collect::<Vec<_>>()
                     .join(",")
             ),
-            MovieLibraryFilter::Genre(ref v) => format!("genre={:?}", encode(v)),
-            MovieLibraryFilter::Collection(ref v) => format!("collection={:?}", encode(v)),
-            MovieLibraryFilter::Director(ref v) => format!("director={:?}", encode(v)),
-            MovieLibraryFilter::Actor(ref v) => format!("actor={:?}", encode(v)),
-            MovieLibraryFilter::Country(ref v) => format!("country={}", v),
-            MovieLibraryFilter::Studio(ref v) => format!("studio={:?}", encode(v)),
+            MovieLibraryFilter::Genre(ref v) => format!("genre={}", encode(v)),
+            MovieLibraryFilter::Collection(ref v) => format!("collection={}", encode(v)),
+            MovieLibraryFilter::Director(ref v) => format!("director={}", encode(v)),
+            MovieLibraryFilter::Actor(ref v) => format!("actor={}", encode(v)),
+            MovieLibraryFilter::Country(ref v) => format!("country={}", escape(v)),
+            MovieLibraryFilter::Studio(ref v) => format!("studio={}", encode(v)),
             MovieLibraryFilter::Resolution(ref v) => format!("resolution={}", v),
-            MovieLibraryFilter::Guid(ref v) => format!("guid={}", v),
-            MovieLibraryFilter::Label(ref v) => format!("label={}", v),
-            MovieLibraryFilter::ContentRating(v) => format!("contentRating={}", v),
+            MovieLibraryFilter::Guid(ref v) => format!("guid={}", escape(v)),
+            MovieLibraryFilter::Label(ref v) => format!("label={}", escape(v)),
+            MovieLibraryFilter::ContentRating(ref v) => format!("contentRating={}", escape(v)),
         }
     }
 }
@@ -73,10 +79,129 @@ pub enum MusicLibraryFilter {
 impl LibraryFilter for MusicLibraryFilter {
     fn format(&self) -> String {
         match *self {
-            MusicLibraryFilter::Genre(ref v) => format!("genre={:?}", encode(v)),
-            MusicLibraryFilter::Country(ref v) => format!("country={}", v),
-            MusicLibraryFilter::Collection(ref v) => format!("collection={:?}", encode(v)),
-            MusicLibraryFilter::Mood(ref v) => format!("mood={}", v),
+            MusicLibraryFilter::Genre(ref v) => format!("genre={}", encode(v)),
+            MusicLibraryFilter::Country(ref v) => format!("country={}", escape(v)),
+            MusicLibraryFilter::Collection(ref v) => format!("collection={}", encode(v)),
+            MusicLibraryFilter::Mood(ref v) => format!("mood={}", escape(v)),
+        }
+    }
+}
+
+/// All allowed filters for a show section
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShowLibraryFilter {
+    Unwatched(bool),
+    Year(Vec<u16>),
+    Genre(Vec<String>),
+    /// eg. `TV-14`
+    ContentRating(String),
+    Collection(Vec<String>),
+    /// the network a show aired on
+    Network(Vec<String>),
+    Actor(Vec<String>),
+    Label(String),
+}
+
+impl LibraryFilter for ShowLibraryFilter {
+    fn format(&self) -> String {
+        match *self {
+            ShowLibraryFilter::Unwatched(v) => format!("unwatched={}", v as u8),
+            ShowLibraryFilter::Year(ref v) => format!(
+                "year={}",
+                v.iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            ShowLibraryFilter::Genre(ref v) => format!("genre={}", encode(v)),
+            ShowLibraryFilter::ContentRating(ref v) => format!("contentRating={}", escape(v)),
+            ShowLibraryFilter::Collection(ref v) => format!("collection={}", encode(v)),
+            ShowLibraryFilter::Network(ref v) => format!("studio={}", encode(v)),
+            ShowLibraryFilter::Actor(ref v) => format!("actor={}", encode(v)),
+            ShowLibraryFilter::Label(ref v) => format!("label={}", escape(v)),
+        }
+    }
+}
+
+/// All allowed filters for a photo section
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhotoLibraryFilter {
+    Year(Vec<u16>),
+    Tag(Vec<String>),
+    /// the maker of the camera, eg. `Apple`
+    Make(String),
+    /// the model of the camera, eg. `iPhone 7`
+    Model(String),
+    Lens(String),
+    /// taken on or after the date, eg. `2017-07-01`
+    TakenAfter(String),
+    /// taken before the date
+    TakenBefore(String),
+}
+
+impl LibraryFilter for PhotoLibraryFilter {
+    fn format(&self) -> String {
+        match *self {
+            PhotoLibraryFilter::Year(ref v) => format!(
+                "year={}",
+                v.iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            PhotoLibraryFilter::Tag(ref v) => format!("tag={}", encode(v)),
+            PhotoLibraryFilter::Make(ref v) => format!("make={}", escape(v)),
+            PhotoLibraryFilter::Model(ref v) => format!("model={}", escape(v)),
+            PhotoLibraryFilter::Lens(ref v) => format!("lens={}", escape(v)),
+            // the operator is part of the key and has to be escaped
+            PhotoLibraryFilter::TakenAfter(ref v) => format!("originallyAvailableAt%3E%3E={}", v),
+            PhotoLibraryFilter::TakenBefore(ref v) => format!("originallyAvailableAt%3C%3C={}", v),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn show_filters() {
+        assert_eq!(ShowLibraryFilter::Unwatched(true).format(), "unwatched=1");
+        assert_eq!(ShowLibraryFilter::Year(vec![2010, 2011]).format(), "year=2010,2011");
+        assert_eq!(
+            ShowLibraryFilter::Network(vec!["1".to_string(), "2".to_string()]).format(),
+            "studio=1,2"
+        );
+        assert_eq!(
+            ShowLibraryFilter::ContentRating("TV-14".to_string()).format(),
+            "contentRating=TV-14"
+        );
+        assert_eq!(
+            MovieLibraryFilter::ContentRating("PG-13".to_string()).format(),
+            "contentRating=PG-13"
+        );
+    }
+
+    #[test]
+    fn photo_filters() {
+        assert_eq!(
+            PhotoLibraryFilter::TakenAfter("2017-07-01".to_string()).format(),
+            "originallyAvailableAt%3E%3E=2017-07-01"
+        );
+        assert_eq!(PhotoLibraryFilter::Year(vec![2017]).format(), "year=2017");
+        assert_eq!(
+            PhotoLibraryFilter::Model("iPhone 7".to_string()).format(),
+            "model=iPhone+7"
+        );
+    }
+
+    #[test]
+    fn movie_filters() {
+        assert_eq!(
+            MovieLibraryFilter::Genre(vec!["Action".to_string(), "Sci-Fi & Fantasy".to_string()])
+                .format(),
+            "genre=Action,Sci-Fi+%26+Fantasy"
+        );
+        assert_eq!(MovieLibraryFilter::Unwachted(true).format(), "unwatched=1");
+    }
+}
@@ -0,0 +1,147 @@
+use std::cmp::Reverse;
+use types::media::common::MediaType;
+use types::sections::Page;
+use url::form_urlencoded::Serializer;
+
+/// Restricts the watch history, all filters have to match
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryFilter {
+    /// id of the account that watched the item
+    Account(String),
+    /// key of the library section the item belongs to
+    LibrarySection(String),
+    /// unix timestamp
+    ViewedAfter(u64),
+    /// unix timestamp
+    ViewedBefore(u64),
+    MediaType(MediaType),
+}
+
+impl HistoryFilter {
+    /// The query parameter of the filter, the operator of the viewed filters is part of the key
+    fn pair(&self) -> (&'static str, String) {
+        match *self {
+            HistoryFilter::Account(ref v) => ("accountID", v.clone()),
+            HistoryFilter::LibrarySection(ref v) => ("librarySectionID", v.clone()),
+            HistoryFilter::ViewedAfter(v) => ("viewedAt>", v.to_string()),
+            HistoryFilter::ViewedBefore(v) => ("viewedAt<", v.to_string()),
+            HistoryFilter::MediaType(ref v) => ("type", v.id().to_string()),
+        }
+    }
+}
+
+/// A single time an item was watched
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryEntry {
+    pub history_key: String,
+    pub key: String,
+    pub rating_key: String,
+    pub parent_key: String,
+    pub grandparent_key: String,
+    pub title: String,
+    pub parent_title: String,
+    pub grandparent_title: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub thumb: String,
+    pub originally_available_at: String,
+    pub viewed_at: String,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: String,
+    #[serde(rename = "accountID")]
+    pub account_id: String,
+    #[serde(rename = "deviceID")]
+    pub device_id: String,
+}
+
+impl HistoryEntry {
+    pub fn media_type(&self) -> Option<MediaType> {
+        MediaType::from_str(self.type_.as_str())
+    }
+
+    /// unix timestamp of when the item was watched
+    pub fn viewed_at(&self) -> Option<u64> {
+        self.viewed_at.parse().ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryContainer {
+    pub size: String,
+    pub total_size: String,
+    pub offset: String,
+    #[serde(rename = "Video", default)]
+    pub videos: Vec<HistoryEntry>,
+    #[serde(rename = "Track", default)]
+    pub tracks: Vec<HistoryEntry>,
+    #[serde(rename = "Photo", default)]
+    pub photos: Vec<HistoryEntry>,
+}
+
+impl HistoryContainer {
+    pub const PATH: &'static str = "/status/sessions/history/all";
+
+    /// The query of a history request, newest entries first
+    pub fn query(filters: &[HistoryFilter]) -> String {
+        let mut query = Serializer::new(String::new());
+        for filter in filters {
+            let (key, value) = filter.pair();
+            query.append_pair(key, value.as_str());
+        }
+        query.append_pair("sort", "viewedAt:desc").finish()
+    }
+
+    /// The entries of the page together with the paging details of the container
+    pub fn into_page(self) -> Page<HistoryEntry> {
+        let (total, offset) = (self.total_size.clone(), self.offset.clone());
+        Page::new(self.into_entries(), &total, &offset)
+    }
+
+    /// All entries of the page, newest first
+    pub fn into_entries(self) -> Vec<HistoryEntry> {
+        let mut entries = self.videos
+            .into_iter()
+            .chain(self.tracks)
+            .chain(self.photos)
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|e| Reverse(e.viewed_at()));
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn history_deserialize() {
+        let xml = fixture!("history.xml");
+        let container: Result<HistoryContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let entries = container.unwrap().into_entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].title, "First Track");
+        assert_eq!(entries[0].media_type(), Some(MediaType::Track));
+        assert_eq!(entries[0].grandparent_title, "Artist");
+        assert_eq!(entries[1].viewed_at(), Some(1511736000));
+        assert_eq!(entries[1].account_id, "1");
+        assert_eq!(entries[2].device_id, "3");
+    }
+
+    #[test]
+    fn history_query() {
+        let query = HistoryContainer::query(&[
+            HistoryFilter::Account("1".to_string()),
+            HistoryFilter::ViewedAfter(1511000000),
+            HistoryFilter::ViewedBefore(1512000000),
+            HistoryFilter::MediaType(MediaType::Episode),
+        ]);
+        assert_eq!(
+            query,
+            "accountID=1&viewedAt%3E=1511000000&viewedAt%3C=1512000000&type=4&sort=viewedAt%3Adesc"
+        );
+    }
+}
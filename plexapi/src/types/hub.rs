@@ -0,0 +1,301 @@
+use types::media::audio::Track;
+use types::media::video::Video;
+use url::form_urlencoded::Serializer;
+
+/// What the items of a hub are
+#[derive(Debug, PartialEq, Clone)]
+pub enum HubType {
+    Movie,
+    Show,
+    Season,
+    Episode,
+    Clip,
+    Artist,
+    Album,
+    Track,
+    Actor,
+    Director,
+    Genre,
+    Collection,
+    Playlist,
+    Other(String),
+}
+
+impl HubType {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            HubType::Movie => "movie",
+            HubType::Show => "show",
+            HubType::Season => "season",
+            HubType::Episode => "episode",
+            HubType::Clip => "clip",
+            HubType::Artist => "artist",
+            HubType::Album => "album",
+            HubType::Track => "track",
+            HubType::Actor => "actor",
+            HubType::Director => "director",
+            HubType::Genre => "genre",
+            HubType::Collection => "collection",
+            HubType::Playlist => "playlist",
+            HubType::Other(ref s) => s.as_str(),
+        }
+    }
+
+    pub fn from_str(s: &str) -> HubType {
+        match s {
+            "movie" => HubType::Movie,
+            "show" => HubType::Show,
+            "season" => HubType::Season,
+            "episode" => HubType::Episode,
+            "clip" => HubType::Clip,
+            "artist" => HubType::Artist,
+            "album" => HubType::Album,
+            "track" => HubType::Track,
+            "actor" => HubType::Actor,
+            "director" => HubType::Director,
+            "genre" => HubType::Genre,
+            "collection" => HubType::Collection,
+            "playlist" => HubType::Playlist,
+            other => HubType::Other(other.to_string()),
+        }
+    }
+}
+
+/// A show, season, artist, album, collection, playlist or tag that shows up in a hub
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HubDirectory {
+    pub rating_key: String,
+    pub key: String,
+    pub parent_rating_key: String,
+    pub parent_key: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub parent_title: String,
+    pub summary: String,
+    pub year: String,
+    pub thumb: String,
+    pub art: String,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: String,
+    pub library_section_title: String,
+    pub leaf_count: String,
+    pub viewed_leaf_count: String,
+    pub child_count: String,
+    pub added_at: String,
+    pub updated_at: String,
+    /// only set for tags, eg. the name of an actor
+    pub tag: String,
+    pub tag_type: String,
+    /// only set for playlists
+    pub playlist_type: String,
+    pub smart: String,
+}
+
+/// An item of a hub, typed by what the hub groups
+#[derive(Debug, PartialEq, Clone)]
+pub enum HubItem {
+    Movie(Video),
+    Episode(Video),
+    Clip(Video),
+    Show(HubDirectory),
+    Season(HubDirectory),
+    Artist(HubDirectory),
+    Album(HubDirectory),
+    Track(Track),
+    /// actors, directors and genres
+    Tag(HubDirectory),
+    Collection(HubDirectory),
+    Playlist(HubDirectory),
+    Other(HubDirectory),
+}
+
+/// Items the server groups together, eg. the movies of a search or "Recently Added"
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Hub {
+    pub hub_key: String,
+    pub key: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub hub_identifier: String,
+    pub context: String,
+    pub size: String,
+    pub more: String,
+    pub style: String,
+    pub promoted: String,
+    #[serde(rename = "Video", default)]
+    pub videos: Vec<Video>,
+    #[serde(rename = "Directory", default)]
+    pub directories: Vec<HubDirectory>,
+    #[serde(rename = "Track", default)]
+    pub tracks: Vec<Track>,
+    #[serde(rename = "Playlist", default)]
+    pub playlists: Vec<HubDirectory>,
+}
+
+impl Hub {
+    pub fn hub_type(&self) -> HubType {
+        HubType::from_str(self.type_.as_str())
+    }
+
+    /// Whether the server left out items, they can be fetched with the `key` of the hub
+    pub fn has_more(&self) -> bool {
+        "1".eq(&self.more)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.videos.is_empty() && self.directories.is_empty() && self.tracks.is_empty()
+            && self.playlists.is_empty()
+    }
+
+    /// Takes the items of `other`, eg. the full list fetched from the `key` of the hub
+    pub fn with_items_of(mut self, other: Hub) -> Hub {
+        self.videos = other.videos;
+        self.directories = other.directories;
+        self.tracks = other.tracks;
+        self.playlists = other.playlists;
+        self.more = "0".to_string();
+        self.size = other.size;
+        self
+    }
+
+    pub fn items(&self) -> Vec<HubItem> {
+        let videos = self.videos.iter().map(|v| match v.type_.as_str() {
+            "episode" => HubItem::Episode(v.clone()),
+            "clip" => HubItem::Clip(v.clone()),
+            _ => HubItem::Movie(v.clone()),
+        });
+        let hub_type = self.hub_type();
+        let directories = self.directories.iter().map(move |d| {
+            let d = d.clone();
+            match hub_type {
+                HubType::Show => HubItem::Show(d),
+                HubType::Season => HubItem::Season(d),
+                HubType::Artist => HubItem::Artist(d),
+                HubType::Album => HubItem::Album(d),
+                HubType::Actor | HubType::Director | HubType::Genre => HubItem::Tag(d),
+                HubType::Collection => HubItem::Collection(d),
+                _ => HubItem::Other(d),
+            }
+        });
+        let tracks = self.tracks.iter().cloned().map(HubItem::Track);
+        let playlists = self.playlists.iter().cloned().map(HubItem::Playlist);
+        videos
+            .chain(directories)
+            .chain(tracks)
+            .chain(playlists)
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HubContainer {
+    pub size: String,
+    #[serde(rename = "Hub", default)]
+    pub hubs: Vec<Hub>,
+}
+
+impl HubContainer {
+    pub const SEARCH: &'static str = "/hubs/search";
+    /// the hubs of the home screen
+    pub const HOME: &'static str = "/hubs";
+    /// followed by the key of a section
+    pub const SECTIONS: &'static str = "/hubs/sections";
+
+    /// The query of a search, `limit` is the maximum number of items per hub
+    pub fn search_query(query: &str, limit: usize, section_id: Option<&str>) -> String {
+        let mut serializer = Serializer::new(String::new());
+        serializer
+            .append_pair("query", query)
+            .append_pair("limit", limit.to_string().as_str());
+        if let Some(id) = section_id {
+            serializer.append_pair("sectionId", id);
+        }
+        serializer.finish()
+    }
+
+    pub fn hub(&self, hub_identifier: &str) -> Option<&Hub> {
+        self.hubs.iter().find(|h| h.hub_identifier.eq(hub_identifier))
+    }
+
+    /// The hubs that have items
+    pub fn into_hubs(self) -> Vec<Hub> {
+        self.hubs.into_iter().filter(|h| !h.is_empty()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn search_deserialize() {
+        let xml = fixture!("search.xml");
+        let container: Result<HubContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let hubs = container.unwrap().into_hubs();
+        // the empty shows hub is left out
+        assert_eq!(hubs.len(), 3);
+        assert_eq!(hubs[0].hub_type(), HubType::Movie);
+        match hubs[0].items()[0] {
+            HubItem::Movie(ref v) => assert_eq!(v.title, "First Movie"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+        assert!(!hubs[1].has_more());
+        match hubs[1].items()[0] {
+            HubItem::Track(ref t) => assert_eq!(t.parent_title, "Album"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+        match hubs[2].items()[0] {
+            HubItem::Tag(ref t) => assert_eq!(t.tag, "First Actor"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+    }
+
+    #[test]
+    fn home_deserialize() {
+        let xml = fixture!("hubs.xml");
+        let container: Result<HubContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let container = container.unwrap();
+        let continue_watching = container.hub("home.continue").unwrap();
+        assert_eq!(continue_watching.hub_type(), HubType::Other("mixed".to_string()));
+        match continue_watching.items()[0] {
+            HubItem::Movie(ref v) => assert_eq!(v.view_offset, "60000"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+        match container.hub("home.music.recent").unwrap().items()[0] {
+            HubItem::Artist(ref d) => assert_eq!(d.title, "Artist"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+
+        let recent = container.hub("home.movies.recent").unwrap().clone();
+        assert!(recent.has_more());
+        let full = Hub {
+            size: "2".to_string(),
+            videos: vec![Video::default(), Video::default()],
+            ..Hub::default()
+        };
+        let recent = recent.with_items_of(full);
+        assert!(!recent.has_more());
+        assert_eq!(recent.title, "Recently Added Movies");
+        assert_eq!(recent.items().len(), 2);
+    }
+
+    #[test]
+    fn search_query() {
+        assert_eq!(
+            HubContainer::search_query("first movie", 3, None),
+            "query=first+movie&limit=3"
+        );
+        assert_eq!(
+            HubContainer::search_query("a&b", 10, Some("1")),
+            "query=a%26b&limit=10&sectionId=1"
+        );
+    }
+}
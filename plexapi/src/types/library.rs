@@ -12,17 +12,32 @@ pub struct PlexLibrary<'a> {
     inner: Library,
     client: Rc<PlexClient<'a>>,
     conn: Connection,
+    /// version of the server the library belongs to, sections check their features against it
+    version: String,
 }
 
 impl<'a> PlexLibrary<'a> {
     pub const PATH: &'static str = "/library";
     pub const SECTIONS: &'static str = "/library/sections";
+    /// followed by the rating key of an item
+    pub const METADATA: &'static str = "/library/metadata";
 
-    pub fn new(inner: Library, client: Rc<PlexClient<'a>>, conn: Connection) -> Self {
+    /// Url of the metadata of the item with the rating key
+    pub fn metadata_url(conn: &Connection, rating_key: &str) -> String {
+        format!("{}{}/{}", conn.endpoint(), PlexLibrary::METADATA, rating_key)
+    }
+
+    pub fn new(
+        inner: Library,
+        client: Rc<PlexClient<'a>>,
+        conn: Connection,
+        version: String,
+    ) -> Self {
         PlexLibrary {
             inner,
             client,
             conn,
+            version,
         }
     }
 
@@ -30,13 +45,16 @@ impl<'a> PlexLibrary<'a> {
         let client = Rc::clone(&self.client);
         let url = format!("{}{}", self.conn.endpoint(), PlexLibrary::SECTIONS);
         let conn = self.conn.clone();
+        let version = self.version.clone();
         self.client
             .get_xml::<Sections>(url.as_str())
             .map(move |container| {
                 container
                     .sections
                     .into_iter()
-                    .map(|section| PlexLibSection::new(section, Rc::clone(&client), conn.clone()))
+                    .map(|section| {
+                        PlexLibSection::new(section, Rc::clone(&client), conn.clone(), version.clone())
+                    })
                     .collect::<Vec<_>>()
             })
     }
@@ -86,6 +104,24 @@ impl<'a> PlexLibrary<'a> {
                 .collect::<Vec<_>>()
         })
     }
+
+    pub fn show_sections(&self) -> impl Future<Item = Vec<ShowSection<'a>>, Error = APIError> {
+        self.sections_by_type(SectionType::Show).map(|sections| {
+            sections
+                .into_iter()
+                .map(|s| ShowSection::from(s))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    pub fn photo_sections(&self) -> impl Future<Item = Vec<PhotoSection<'a>>, Error = APIError> {
+        self.sections_by_type(SectionType::Photo).map(|sections| {
+            sections
+                .into_iter()
+                .map(|s| PhotoSection::from(s))
+                .collect::<Vec<_>>()
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -1,11 +1,66 @@
 use client::PlexClient;
 use types::device::Connection;
+use types::library::PlexLibrary;
+use types::sections::Page;
+use types::media::common::{first, Similar};
 use std::rc::Rc;
 use futures::Future;
 use errors::APIError;
+use types::session::{Player, Session, SessionUser};
+use types::transcode::TranscodeSession;
 
 pub trait Audio {}
 
+#[derive(Debug, Clone)]
+pub struct PlexArtist<'a> {
+    pub inner: Artist,
+    conn: Connection,
+    client: Rc<PlexClient<'a>>,
+}
+
+impl<'a> PlexArtist<'a> {
+    pub fn new(inner: Artist, conn: Connection, client: Rc<PlexClient<'a>>) -> Self {
+        PlexArtist {
+            inner,
+            conn,
+            client,
+        }
+    }
+
+    pub fn albums(&self) -> impl Future<Item = Vec<PlexAlbum<'a>>, Error = APIError> {
+        let url = format!("{}{}", self.conn.endpoint(), self.inner.key);
+        albums(&self.client, &self.conn, url.as_str())
+    }
+
+    /// The tracks of all albums
+    pub fn tracks(&self) -> impl Future<Item = Vec<PlexTrack<'a>>, Error = APIError> {
+        let url = format!(
+            "{}/allLeaves",
+            PlexLibrary::metadata_url(&self.conn, self.inner.rating_key.as_str())
+        );
+        tracks(&self.client, &self.conn, url.as_str())
+    }
+
+    /// The most popular tracks, ordered by `Track::popularity`
+    pub fn top_tracks(&self, limit: usize) -> impl Future<Item = Vec<PlexTrack<'a>>, Error = APIError> {
+        self.tracks().map(move |mut tracks| {
+            tracks.sort_by_key(|t| ::std::cmp::Reverse(t.inner.popularity()));
+            tracks.truncate(limit);
+            tracks
+        })
+    }
+
+    /// Names of similar artists, they do not have to be part of the library
+    pub fn similar(&self) -> impl Future<Item = Vec<Similar>, Error = APIError> {
+        let url = PlexLibrary::metadata_url(&self.conn, self.inner.rating_key.as_str());
+        self.client
+            .get_xml::<ArtistContainer>(url.as_str())
+            .and_then(|container| first(container.artists))
+            .map(|artist| artist.similar)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct PlexAlbum<'a> {
     pub inner: Album,
     conn: Connection,
@@ -21,18 +76,189 @@ impl<'a> PlexAlbum<'a> {
         }
     }
 
-    pub fn tracks(&self) -> impl Future<Item = Vec<Track>, Error = APIError> {
-        let url = format!("{}/{}/children", self.conn.endpoint(), self.inner.key);
+    pub fn tracks(&self) -> impl Future<Item = Vec<PlexTrack<'a>>, Error = APIError> {
+        // the album key already points to its children
+        let url = format!("{}{}", self.conn.endpoint(), self.inner.key);
+        tracks(&self.client, &self.conn, url.as_str())
+    }
+
+    pub fn artist(&self) -> impl Future<Item = PlexArtist<'a>, Error = APIError> {
+        artist(
+            &self.client,
+            &self.conn,
+            self.inner.parent_rating_key.as_str(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlexTrack<'a> {
+    pub inner: Track,
+    conn: Connection,
+    client: Rc<PlexClient<'a>>,
+}
+
+impl<'a> PlexTrack<'a> {
+    pub fn new(inner: Track, conn: Connection, client: Rc<PlexClient<'a>>) -> Self {
+        PlexTrack {
+            inner,
+            conn,
+            client,
+        }
+    }
+
+    pub fn album(&self) -> impl Future<Item = PlexAlbum<'a>, Error = APIError> {
+        let url = PlexLibrary::metadata_url(&self.conn, self.inner.parent_rating_key.as_str());
+        let conn = self.conn.clone();
+        let client = Rc::clone(&self.client);
         self.client
-            .get_xml::<TrackContainer>(url.as_str())
-            .map(move |container| container.tracks)
+            .get_xml::<AlbumContainer>(url.as_str())
+            .and_then(|container| first(container.albums))
+            .map(move |album| PlexAlbum::new(album, conn, client))
+    }
+
+    pub fn artist(&self) -> impl Future<Item = PlexArtist<'a>, Error = APIError> {
+        artist(
+            &self.client,
+            &self.conn,
+            self.inner.grandparent_rating_key.as_str(),
+        )
     }
 }
 
+fn artist<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    rating_key: &str,
+) -> impl Future<Item = PlexArtist<'a>, Error = APIError> {
+    let url = PlexLibrary::metadata_url(conn, rating_key);
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client
+        .get_xml::<ArtistContainer>(url.as_str())
+        .and_then(|container| first(container.artists))
+        .map(move |artist| PlexArtist::new(artist, conn, wrap_client))
+}
+
+pub(crate) fn albums<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    url: &str,
+) -> impl Future<Item = Vec<PlexAlbum<'a>>, Error = APIError> {
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client.get_xml::<AlbumContainer>(url).map(move |container| {
+        container
+            .albums
+            .into_iter()
+            .map(|album| PlexAlbum::new(album, conn.clone(), Rc::clone(&wrap_client)))
+            .collect::<Vec<_>>()
+    })
+}
+
+pub(crate) fn tracks<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    url: &str,
+) -> impl Future<Item = Vec<PlexTrack<'a>>, Error = APIError> {
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client.get_xml::<TrackContainer>(url).map(move |container| {
+        container
+            .tracks
+            .into_iter()
+            .map(|track| PlexTrack::new(track, conn.clone(), Rc::clone(&wrap_client)))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// A page of the artists of the url, see `fetch_pages`
+pub(crate) fn artist_page<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    url: &str,
+    start: usize,
+    max: usize,
+) -> impl Future<Item = Page<PlexArtist<'a>>, Error = APIError> {
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client
+        .get_xml_container::<ArtistContainer>(url, start, max)
+        .map(move |container| {
+            let artists = container
+                .artists
+                .into_iter()
+                .map(|artist| PlexArtist::new(artist, conn.clone(), Rc::clone(&wrap_client)))
+                .collect::<Vec<_>>();
+            Page::new(artists, &container.total_size, &container.offset)
+        })
+}
+
+/// A page of the tracks of the url, see `fetch_pages`
+pub(crate) fn track_page<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    url: &str,
+    start: usize,
+    max: usize,
+) -> impl Future<Item = Page<PlexTrack<'a>>, Error = APIError> {
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client
+        .get_xml_container::<TrackContainer>(url, start, max)
+        .map(move |container| {
+            let tracks = container
+                .tracks
+                .into_iter()
+                .map(|track| PlexTrack::new(track, conn.clone(), Rc::clone(&wrap_client)))
+                .collect::<Vec<_>>();
+            Page::new(tracks, &container.total_size, &container.offset)
+        })
+}
+
+/// An artist of a music section, its key points to its albums
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Artist {
+    pub rating_key: String,
+    pub key: String,
+    pub guid: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub summary: String,
+    pub index: String,
+    pub thumb: String,
+    pub art: String,
+    pub added_at: String,
+    pub updated_at: String,
+    /// only part of the metadata of a single artist
+    #[serde(rename = "Similar", default)]
+    pub similar: Vec<Similar>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArtistContainer {
+    pub size: String,
+    pub total_size: String,
+    pub offset: String,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: String,
+    pub library_section_title: String,
+    pub title1: String,
+    pub title2: String,
+    pub view_group: String,
+    #[serde(rename = "Directory", default)]
+    pub artists: Vec<Artist>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct AlbumContainer {
     pub size: String,
+    pub total_size: String,
+    pub offset: String,
     pub allow_sync: String,
     pub art: String,
     pub identifier: String,
@@ -83,6 +309,8 @@ pub struct Album {
 #[serde(rename_all = "camelCase", default)]
 pub struct TrackContainer {
     pub size: String,
+    pub total_size: String,
+    pub offset: String,
     pub allow_sync: String,
     pub art: String,
     pub identifier: String,
@@ -136,6 +364,25 @@ pub struct Track {
     pub updated_at: String,
     #[serde(rename = "Media")]
     pub media: Vec<TrackMedia>,
+    /// only set if the track is part of a playback session
+    pub view_offset: String,
+    pub session_key: String,
+    #[serde(rename = "User")]
+    pub user: Option<SessionUser>,
+    #[serde(rename = "Player")]
+    pub player: Option<Player>,
+    #[serde(rename = "Session")]
+    pub session: Option<Session>,
+    #[serde(rename = "TranscodeSession")]
+    pub transcode_session: Option<TranscodeSession>,
+}
+
+impl Track {
+    /// Popularity of the track as reported by the metadata agent in `ratingCount`, higher is
+    /// more popular
+    pub fn popularity(&self) -> u64 {
+        self.rating_count.parse().unwrap_or(0)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
@@ -189,6 +436,16 @@ leafCount="6" addedAt="1514064996" updatedAt="234123412">
         assert!(server.is_ok());
     }
 
+    #[test]
+    fn artist_deserialize() {
+        let xml = fixture!("artist.xml");
+        let container: Result<ArtistContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let artist = &container.unwrap().artists[0];
+        assert_eq!(artist.similar.len(), 2);
+        assert_eq!(artist.similar[1].tag, "Third Artist");
+    }
+
     #[test]
     fn track_container_deserialize() {
         let xml = r##"<MediaContainer size="6" allowSync="1"
@@ -1,3 +1,5 @@
+use errors::APIError;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 #[serde(default)]
 pub struct Genre {
@@ -41,3 +43,71 @@ pub struct Similar {
     pub filter: String,
     pub tag: String,
 }
+
+/// The type of a metadata item, plex identifies them by number in queries
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum MediaType {
+    Movie,
+    Show,
+    Season,
+    Episode,
+    Artist,
+    Album,
+    Track,
+    Clip,
+    Photo,
+    PhotoAlbum,
+}
+
+impl MediaType {
+    pub fn id(&self) -> u8 {
+        match *self {
+            MediaType::Movie => 1,
+            MediaType::Show => 2,
+            MediaType::Season => 3,
+            MediaType::Episode => 4,
+            MediaType::Artist => 8,
+            MediaType::Album => 9,
+            MediaType::Track => 10,
+            MediaType::Clip => 12,
+            MediaType::Photo => 13,
+            MediaType::PhotoAlbum => 14,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            MediaType::Movie => "movie",
+            MediaType::Show => "show",
+            MediaType::Season => "season",
+            MediaType::Episode => "episode",
+            MediaType::Artist => "artist",
+            MediaType::Album => "album",
+            MediaType::Track => "track",
+            MediaType::Clip => "clip",
+            MediaType::Photo => "photo",
+            MediaType::PhotoAlbum => "photoalbum",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<MediaType> {
+        match s {
+            "movie" => Some(MediaType::Movie),
+            "show" => Some(MediaType::Show),
+            "season" => Some(MediaType::Season),
+            "episode" => Some(MediaType::Episode),
+            "artist" => Some(MediaType::Artist),
+            "album" => Some(MediaType::Album),
+            "track" => Some(MediaType::Track),
+            "clip" => Some(MediaType::Clip),
+            "photo" => Some(MediaType::Photo),
+            "photoalbum" => Some(MediaType::PhotoAlbum),
+            _ => None,
+        }
+    }
+}
+
+/// The only item of a metadata container
+pub(crate) fn first<T>(items: Vec<T>) -> Result<T, APIError> {
+    items.into_iter().next().ok_or(APIError::ReadError)
+}
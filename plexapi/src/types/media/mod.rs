@@ -1,3 +1,5 @@
 pub mod video;
 pub mod audio;
+pub mod photo;
+pub mod show;
 pub mod common;
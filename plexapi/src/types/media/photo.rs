@@ -0,0 +1,219 @@
+use client::PlexClient;
+use errors::APIError;
+use futures::Future;
+use std::rc::Rc;
+use types::device::Connection;
+use types::media::video::Video;
+
+/// An album of a photo section, its key points to the photos, clips and albums in it
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PhotoAlbum {
+    pub rating_key: String,
+    pub key: String,
+    pub parent_rating_key: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub summary: String,
+    pub index: String,
+    pub thumb: String,
+    pub composite: String,
+    pub child_count: String,
+    pub originally_available_at: String,
+    pub added_at: String,
+    pub updated_at: String,
+}
+
+/// The file of a photo
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PhotoPart {
+    pub id: String,
+    pub key: String,
+    pub file: String,
+    pub size: String,
+    pub container: String,
+}
+
+/// The server reads these from the EXIF data of the file
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PhotoMedia {
+    pub id: String,
+    pub width: String,
+    pub height: String,
+    pub aspect_ratio: String,
+    pub container: String,
+    pub aperture: String,
+    pub exposure: String,
+    pub iso: String,
+    pub lens: String,
+    pub make: String,
+    pub model: String,
+    #[serde(rename = "Part")]
+    pub part: PhotoPart,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Photo {
+    pub rating_key: String,
+    pub key: String,
+    pub parent_rating_key: String,
+    pub parent_key: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    /// the album the photo is in
+    pub parent_title: String,
+    pub summary: String,
+    pub index: String,
+    pub year: String,
+    pub thumb: String,
+    /// the capture date, eg. `2017-07-16`
+    pub originally_available_at: String,
+    pub added_at: String,
+    pub updated_at: String,
+    #[serde(rename = "Media")]
+    pub media: Vec<PhotoMedia>,
+}
+
+impl Photo {
+    /// The date the photo was taken, eg. `2017-07-16`
+    pub fn captured_at(&self) -> Option<&str> {
+        match self.originally_available_at.is_empty() {
+            true => None,
+            _ => Some(self.originally_available_at.as_str()),
+        }
+    }
+
+    /// Maker and model of the camera, eg. `Apple iPhone 7`
+    pub fn camera(&self) -> Option<String> {
+        let media = self.media.first()?;
+        match (media.make.is_empty(), media.model.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(media.make.clone()),
+            (true, false) => Some(media.model.clone()),
+            _ => Some(format!("{} {}", media.make, media.model)),
+        }
+    }
+
+    pub fn lens(&self) -> Option<&str> {
+        self.media
+            .first()
+            .map(|m| m.lens.as_str())
+            .filter(|l| !l.is_empty())
+    }
+
+    /// Width and height in pixels
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        let media = self.media.first()?;
+        Some((media.width.parse().ok()?, media.height.parse().ok()?))
+    }
+}
+
+/// The content of a photo section or album.
+///
+/// The server groups the children by their kind, albums come first.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PhotoContainer {
+    pub size: String,
+    pub total_size: String,
+    pub offset: String,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: String,
+    pub library_section_title: String,
+    pub title1: String,
+    pub title2: String,
+    pub view_group: String,
+    #[serde(rename = "Directory", default)]
+    pub albums: Vec<PhotoAlbum>,
+    #[serde(rename = "Photo", default)]
+    pub photos: Vec<Photo>,
+    #[serde(rename = "Video", default)]
+    pub clips: Vec<Video>,
+}
+
+impl PhotoContainer {
+    pub fn into_items<'a>(
+        self,
+        conn: &Connection,
+        client: &Rc<PlexClient<'a>>,
+    ) -> Vec<PhotoItem<'a>> {
+        let albums = self.albums.into_iter().map(|album| {
+            PhotoItem::Album(PlexPhotoAlbum::new(album, conn.clone(), Rc::clone(client)))
+        });
+        albums
+            .chain(self.photos.into_iter().map(PhotoItem::Photo))
+            .chain(self.clips.into_iter().map(PhotoItem::Clip))
+            .collect()
+    }
+}
+
+/// An entry of a photo section or album
+#[derive(Debug, Clone)]
+pub enum PhotoItem<'a> {
+    Album(PlexPhotoAlbum<'a>),
+    Photo(Photo),
+    Clip(Video),
+}
+
+#[derive(Debug, Clone)]
+pub struct PlexPhotoAlbum<'a> {
+    pub inner: PhotoAlbum,
+    conn: Connection,
+    client: Rc<PlexClient<'a>>,
+}
+
+impl<'a> PlexPhotoAlbum<'a> {
+    pub fn new(inner: PhotoAlbum, conn: Connection, client: Rc<PlexClient<'a>>) -> Self {
+        PlexPhotoAlbum {
+            inner,
+            conn,
+            client,
+        }
+    }
+
+    /// The photos, clips and albums in the album
+    pub fn items(&self) -> impl Future<Item = Vec<PhotoItem<'a>>, Error = APIError> {
+        let url = format!("{}{}", self.conn.endpoint(), self.inner.key);
+        let conn = self.conn.clone();
+        let client = Rc::clone(&self.client);
+        self.client
+            .get_xml::<PhotoContainer>(url.as_str())
+            .map(move |container| container.into_items(&conn, &client))
+    }
+
+    pub fn photos(&self) -> impl Future<Item = Vec<Photo>, Error = APIError> {
+        let url = format!("{}{}", self.conn.endpoint(), self.inner.key);
+        self.client
+            .get_xml::<PhotoContainer>(url.as_str())
+            .map(|container| container.photos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn photo_container_deserialize() {
+        let xml = fixture!("photos.xml");
+        let container: Result<PhotoContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let container = container.unwrap();
+        assert_eq!(container.albums.len(), 1);
+        assert_eq!(container.clips[0].type_, "clip");
+
+        let photo = &container.photos[0];
+        assert_eq!(photo.captured_at(), Some("2017-07-16"));
+        assert_eq!(photo.camera(), Some("Apple iPhone 7".to_string()));
+        assert_eq!(photo.lens(), Some("iPhone 7 back camera 3.99mm f/1.8"));
+        assert_eq!(photo.dimensions(), Some((4032, 3024)));
+        assert_eq!(photo.media[0].part.size, "2040047");
+        assert_eq!(Photo::default().camera(), None);
+    }
+}
@@ -0,0 +1,298 @@
+use client::PlexClient;
+use errors::APIError;
+use futures::Future;
+use std::rc::Rc;
+use types::device::Connection;
+use types::library::PlexLibrary;
+use types::media::common::first;
+use types::media::video::Video;
+
+/// A show of a show section, its key points to its seasons
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Show {
+    pub rating_key: String,
+    pub key: String,
+    pub guid: String,
+    pub studio: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub content_rating: String,
+    pub summary: String,
+    pub index: String,
+    pub rating: String,
+    pub year: String,
+    pub thumb: String,
+    pub art: String,
+    pub banner: String,
+    pub theme: String,
+    pub duration: String,
+    pub originally_available_at: String,
+    /// number of episodes
+    pub leaf_count: String,
+    pub viewed_leaf_count: String,
+    /// number of seasons
+    pub child_count: String,
+    pub added_at: String,
+    pub updated_at: String,
+}
+
+impl Show {
+    pub fn episode_count(&self) -> usize {
+        self.leaf_count.parse().unwrap_or(0)
+    }
+
+    pub fn is_watched(&self) -> bool {
+        !self.leaf_count.is_empty() && self.leaf_count.eq(&self.viewed_leaf_count)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShowContainer {
+    pub size: String,
+    pub total_size: String,
+    pub offset: String,
+    #[serde(rename = "librarySectionID")]
+    pub library_section_id: String,
+    pub library_section_title: String,
+    pub title1: String,
+    pub title2: String,
+    pub view_group: String,
+    #[serde(rename = "Directory", default)]
+    pub shows: Vec<Show>,
+}
+
+/// A season of a show, its key points to its episodes
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Season {
+    pub rating_key: String,
+    pub key: String,
+    pub parent_rating_key: String,
+    pub parent_key: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub parent_title: String,
+    pub summary: String,
+    pub index: String,
+    pub thumb: String,
+    pub parent_thumb: String,
+    pub leaf_count: String,
+    pub viewed_leaf_count: String,
+    pub added_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SeasonContainer {
+    pub size: String,
+    pub parent_title: String,
+    pub title1: String,
+    pub title2: String,
+    #[serde(rename = "Directory", default)]
+    pub seasons: Vec<Season>,
+}
+
+impl SeasonContainer {
+    /// The children of a show also contain an "All episodes" entry that is no season
+    pub fn into_seasons(self) -> Vec<Season> {
+        self.seasons
+            .into_iter()
+            .filter(|s| s.type_.eq("season"))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EpisodeContainer {
+    pub size: String,
+    pub parent_title: String,
+    pub grandparent_title: String,
+    pub title1: String,
+    pub title2: String,
+    #[serde(rename = "Video", default)]
+    pub episodes: Vec<Video>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlexShow<'a> {
+    pub inner: Show,
+    conn: Connection,
+    client: Rc<PlexClient<'a>>,
+}
+
+impl<'a> PlexShow<'a> {
+    pub fn new(inner: Show, conn: Connection, client: Rc<PlexClient<'a>>) -> Self {
+        PlexShow {
+            inner,
+            conn,
+            client,
+        }
+    }
+
+    pub fn seasons(&self) -> impl Future<Item = Vec<PlexSeason<'a>>, Error = APIError> {
+        let url = format!("{}{}", self.conn.endpoint(), self.inner.key);
+        let conn = self.conn.clone();
+        let client = Rc::clone(&self.client);
+        self.client
+            .get_xml::<SeasonContainer>(url.as_str())
+            .map(move |container| {
+                container
+                    .into_seasons()
+                    .into_iter()
+                    .map(|season| PlexSeason::new(season, conn.clone(), Rc::clone(&client)))
+                    .collect::<Vec<_>>()
+            })
+    }
+
+    /// The episodes of all seasons
+    pub fn episodes(&self) -> impl Future<Item = Vec<PlexEpisode<'a>>, Error = APIError> {
+        let url = format!(
+            "{}/allLeaves",
+            PlexLibrary::metadata_url(&self.conn, self.inner.rating_key.as_str())
+        );
+        episodes(&self.client, &self.conn, url.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlexSeason<'a> {
+    pub inner: Season,
+    conn: Connection,
+    client: Rc<PlexClient<'a>>,
+}
+
+impl<'a> PlexSeason<'a> {
+    pub fn new(inner: Season, conn: Connection, client: Rc<PlexClient<'a>>) -> Self {
+        PlexSeason {
+            inner,
+            conn,
+            client,
+        }
+    }
+
+    pub fn episodes(&self) -> impl Future<Item = Vec<PlexEpisode<'a>>, Error = APIError> {
+        let url = format!("{}{}", self.conn.endpoint(), self.inner.key);
+        episodes(&self.client, &self.conn, url.as_str())
+    }
+
+    pub fn show(&self) -> impl Future<Item = PlexShow<'a>, Error = APIError> {
+        show(
+            &self.client,
+            &self.conn,
+            self.inner.parent_rating_key.as_str(),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlexEpisode<'a> {
+    pub inner: Video,
+    conn: Connection,
+    client: Rc<PlexClient<'a>>,
+}
+
+impl<'a> PlexEpisode<'a> {
+    pub fn new(inner: Video, conn: Connection, client: Rc<PlexClient<'a>>) -> Self {
+        PlexEpisode {
+            inner,
+            conn,
+            client,
+        }
+    }
+
+    pub fn season(&self) -> impl Future<Item = PlexSeason<'a>, Error = APIError> {
+        let url = PlexLibrary::metadata_url(&self.conn, self.inner.parent_rating_key.as_str());
+        let conn = self.conn.clone();
+        let client = Rc::clone(&self.client);
+        self.client
+            .get_xml::<SeasonContainer>(url.as_str())
+            .and_then(|container| first(container.seasons))
+            .map(move |season| PlexSeason::new(season, conn, client))
+    }
+
+    pub fn show(&self) -> impl Future<Item = PlexShow<'a>, Error = APIError> {
+        show(
+            &self.client,
+            &self.conn,
+            self.inner.grandparent_rating_key.as_str(),
+        )
+    }
+}
+
+fn show<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    rating_key: &str,
+) -> impl Future<Item = PlexShow<'a>, Error = APIError> {
+    let url = PlexLibrary::metadata_url(conn, rating_key);
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client
+        .get_xml::<ShowContainer>(url.as_str())
+        .and_then(|container| first(container.shows))
+        .map(move |show| PlexShow::new(show, conn, wrap_client))
+}
+
+fn episodes<'a>(
+    client: &Rc<PlexClient<'a>>,
+    conn: &Connection,
+    url: &str,
+) -> impl Future<Item = Vec<PlexEpisode<'a>>, Error = APIError> {
+    let conn = conn.clone();
+    let wrap_client = Rc::clone(client);
+    client
+        .get_xml::<EpisodeContainer>(url)
+        .map(move |container| {
+            container
+                .episodes
+                .into_iter()
+                .map(|episode| PlexEpisode::new(episode, conn.clone(), Rc::clone(&wrap_client)))
+                .collect::<Vec<_>>()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn shows_deserialize() {
+        let xml = fixture!("shows.xml");
+        let container: Result<ShowContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let show = &container.unwrap().shows[0];
+        assert_eq!(show.episode_count(), 3);
+        assert!(!show.is_watched());
+    }
+
+    #[test]
+    fn seasons_deserialize() {
+        let xml = fixture!("seasons.xml");
+        let container: Result<SeasonContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let seasons = container.unwrap().into_seasons();
+        assert_eq!(seasons.len(), 2);
+        assert_eq!(seasons[1].index, "2");
+        assert_eq!(seasons[1].parent_rating_key, "100");
+    }
+
+    #[test]
+    fn episodes_deserialize() {
+        let xml = fixture!("episodes.xml");
+        let container: Result<EpisodeContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let episodes = container.unwrap().episodes;
+        assert_eq!(episodes.len(), 2);
+        let episode = &episodes[0];
+        assert_eq!(episode.grandparent_rating_key, "100");
+        assert_eq!(episode.parent_index, "1");
+        assert_eq!(episode.index, "1");
+    }
+}
@@ -1,3 +1,6 @@
+use types::session::{Player, Session, SessionUser};
+use types::transcode::TranscodeSession;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Video {
@@ -7,6 +10,15 @@ pub struct Video {
     pub type_: String,
     pub title: String,
     pub summary: String,
+    /// only set for episodes, the season is the parent and the show the grandparent
+    pub parent_rating_key: String,
+    pub grandparent_rating_key: String,
+    pub parent_key: String,
+    pub grandparent_key: String,
+    pub parent_title: String,
+    pub grandparent_title: String,
+    pub index: String,
+    pub parent_index: String,
     pub view_offset: String,
     pub last_viewed_at: String,
     pub year: String,
@@ -21,6 +33,16 @@ pub struct Video {
     pub created_at_tz_offset: String,
     #[serde(rename = "Media")]
     pub media: Vec<VideoMedia>,
+    /// only set if the video is part of a playback session
+    pub session_key: String,
+    #[serde(rename = "User")]
+    pub user: Option<SessionUser>,
+    #[serde(rename = "Player")]
+    pub player: Option<Player>,
+    #[serde(rename = "Session")]
+    pub session: Option<Session>,
+    #[serde(rename = "TranscodeSession")]
+    pub transcode_session: Option<TranscodeSession>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
@@ -65,6 +87,10 @@ pub struct Part {
 #[serde(rename_all = "camelCase")]
 pub struct VideoContainer {
     pub size: String,
+    #[serde(default)]
+    pub total_size: String,
+    #[serde(default)]
+    pub offset: String,
     pub allow_sync: String,
     pub art: String,
     pub identifier: String,
@@ -33,6 +33,10 @@ impl<'a> Into<XPlexToken> for &'a PlexToken {
 }
 
 pub mod account;
+pub mod activity;
+pub mod browse;
+pub mod butler;
+pub mod companion;
 pub mod device;
 pub mod server;
 pub mod library;
@@ -40,3 +44,13 @@ pub mod media;
 pub mod playlist;
 pub mod sections;
 pub mod filter;
+pub mod feature;
+pub mod history;
+pub mod hub;
+pub mod notification;
+pub mod preferences;
+pub mod session;
+pub mod statistics;
+pub mod timeline;
+pub mod transcode;
+pub mod updater;
@@ -0,0 +1,408 @@
+use errors::APIError;
+use futures::{Async, Future, Poll, Sink, Stream};
+use serde::{Deserialize, Deserializer};
+use serde_json::{self, Value};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio_core::reactor::{Handle, Timeout};
+use websocket::{ClientBuilder, OwnedMessage};
+use websocket::async::Stream as AsyncStream;
+use websocket::async::client::{Client, ClientNew};
+
+/// Plex is not consistent whether ids are sent as numbers or strings
+fn lenient_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(s) => s,
+        Value::Null => String::new(),
+        v => v.to_string(),
+    })
+}
+
+/// State change of a playback session
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaySessionState {
+    #[serde(deserialize_with = "lenient_string")]
+    pub session_key: String,
+    pub client_identifier: String,
+    pub guid: String,
+    #[serde(deserialize_with = "lenient_string")]
+    pub rating_key: String,
+    pub url: String,
+    pub key: String,
+    pub view_offset: u64,
+    #[serde(rename = "playQueueItemID")]
+    pub play_queue_item_id: u64,
+    pub state: String,
+    pub transcode_session: String,
+}
+
+/// A library item that was created, changed or deleted
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimelineEntry {
+    pub identifier: String,
+    #[serde(rename = "sectionID", deserialize_with = "lenient_string")]
+    pub section_id: String,
+    #[serde(rename = "itemID", deserialize_with = "lenient_string")]
+    pub item_id: String,
+    #[serde(rename = "type")]
+    pub type_: i32,
+    pub title: String,
+    pub state: i32,
+    pub metadata_state: String,
+    pub media_state: String,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivityState {
+    pub uuid: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub cancellable: bool,
+    #[serde(rename = "userID", deserialize_with = "lenient_string")]
+    pub user_id: String,
+    pub title: String,
+    pub subtitle: String,
+    pub progress: u32,
+}
+
+/// Progress of a long running server activity, eg. a library scan
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivityNotification {
+    /// `started`, `updated` or `ended`
+    pub event: String,
+    pub uuid: String,
+    #[serde(rename = "Activity")]
+    pub activity: ActivityState,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranscodeSessionState {
+    pub key: String,
+    pub throttled: bool,
+    pub complete: bool,
+    pub progress: f32,
+    pub speed: f32,
+    pub duration: u64,
+    pub remaining: u64,
+    pub context: String,
+    pub source_video_codec: String,
+    pub source_audio_codec: String,
+    pub video_decision: String,
+    pub audio_decision: String,
+    pub protocol: String,
+    pub container: String,
+    pub video_codec: String,
+    pub audio_codec: String,
+    pub audio_channels: u32,
+    pub transcode_hw_requested: bool,
+    pub transcode_hw_full_pipeline: bool,
+}
+
+/// Whether the server is reachable from outside through plex.tv
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReachabilityNotification {
+    pub reachability: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationContainer {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub size: u32,
+    #[serde(rename = "PlaySessionStateNotification")]
+    pub play_sessions: Vec<PlaySessionState>,
+    #[serde(rename = "TimelineEntry")]
+    pub timeline: Vec<TimelineEntry>,
+    #[serde(rename = "ActivityNotification")]
+    pub activities: Vec<ActivityNotification>,
+    #[serde(rename = "TranscodeSession")]
+    pub transcode_sessions: Vec<TranscodeSessionState>,
+    #[serde(rename = "ReachabilityNotification")]
+    pub reachability: Vec<ReachabilityNotification>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+struct NotificationMessage {
+    #[serde(rename = "NotificationContainer")]
+    container: NotificationContainer,
+}
+
+/// A single event sent by the server
+#[derive(Debug, PartialEq, Clone)]
+pub enum Notification {
+    Playing(PlaySessionState),
+    Timeline(TimelineEntry),
+    Activity(ActivityNotification),
+    TranscodeSessionUpdate(TranscodeSessionState),
+    TranscodeSessionEnd(TranscodeSessionState),
+    Reachability(ReachabilityNotification),
+    /// notification types that are not modelled, with their type name
+    Other(String),
+}
+
+impl Notification {
+    /// Splits a message of the notification websocket into its events
+    pub fn parse(message: &str) -> Result<Vec<Notification>, APIError> {
+        let message: NotificationMessage =
+            serde_json::from_str(message).map_err(|e| APIError::ParseError(e.to_string()))?;
+        let container = message.container;
+        let notifications = match container.type_.as_str() {
+            "playing" => container
+                .play_sessions
+                .into_iter()
+                .map(Notification::Playing)
+                .collect(),
+            "timeline" => container
+                .timeline
+                .into_iter()
+                .map(Notification::Timeline)
+                .collect(),
+            "activity" => container
+                .activities
+                .into_iter()
+                .map(Notification::Activity)
+                .collect(),
+            "transcodeSession.update" => container
+                .transcode_sessions
+                .into_iter()
+                .map(Notification::TranscodeSessionUpdate)
+                .collect(),
+            "transcodeSession.end" => container
+                .transcode_sessions
+                .into_iter()
+                .map(Notification::TranscodeSessionEnd)
+                .collect(),
+            "reachability" => container
+                .reachability
+                .into_iter()
+                .map(Notification::Reachability)
+                .collect(),
+            other => vec![Notification::Other(other.to_string())],
+        };
+        Ok(notifications)
+    }
+}
+
+type WebSocket = Client<Box<AsyncStream + Send>>;
+
+enum StreamState {
+    Connecting(ClientNew<Box<AsyncStream + Send>>),
+    Connected(WebSocket),
+    Waiting(Timeout),
+}
+
+enum Step {
+    Connect,
+    Connected(WebSocket),
+    Reconnect,
+    Message(OwnedMessage),
+}
+
+/// `Stream` of the notifications a server sends over its websocket.
+///
+/// Whenever the connection fails or gets closed the stream waits for the reconnect delay and
+/// connects again, so it only ends when it is dropped.
+pub struct NotificationStream {
+    url: String,
+    handle: Handle,
+    reconnect_delay: Duration,
+    state: StreamState,
+    pending: VecDeque<Notification>,
+}
+
+impl NotificationStream {
+    pub const PATH: &'static str = "/:/websockets/notifications";
+
+    /// Subscribes to the websocket at `url`, eg. `ws://127.0.0.1:32400/:/websockets/notifications`
+    pub fn connect(url: &str, handle: &Handle) -> Result<Self, APIError> {
+        ClientBuilder::new(url).map_err(|e| APIError::ParseError(e.to_string()))?;
+        Ok(NotificationStream {
+            url: url.to_string(),
+            handle: handle.clone(),
+            reconnect_delay: Duration::from_secs(5),
+            state: NotificationStream::connecting(url, handle),
+            pending: VecDeque::new(),
+        })
+    }
+
+    pub fn set_reconnect_delay(&mut self, delay: Duration) {
+        self.reconnect_delay = delay;
+    }
+
+    #[inline]
+    pub fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    fn connecting(url: &str, handle: &Handle) -> StreamState {
+        // the url was validated when the stream was created
+        let builder = ClientBuilder::new(url).unwrap();
+        StreamState::Connecting(builder.async_connect(None, handle))
+    }
+
+    fn waiting(&self) -> Result<StreamState, APIError> {
+        Ok(StreamState::Waiting(Timeout::new(
+            self.reconnect_delay,
+            &self.handle,
+        )?))
+    }
+
+    /// Handles a message of the websocket, returns false if the connection has to be given up
+    fn on_message(&mut self, message: OwnedMessage) -> bool {
+        match message {
+            OwnedMessage::Text(text) => match Notification::parse(text.as_str()) {
+                Ok(notifications) => self.pending.extend(notifications),
+                Err(e) => warn!("Skipping malformed notification {}: {:?}", text, e),
+            },
+            OwnedMessage::Ping(data) => {
+                if let StreamState::Connected(ref mut socket) = self.state {
+                    let sent = socket
+                        .start_send(OwnedMessage::Pong(data))
+                        .and_then(|_| socket.poll_complete());
+                    if let Err(e) = sent {
+                        warn!("Failed to answer the ping of the notifications: {:?}", e);
+                        return false;
+                    }
+                }
+            }
+            OwnedMessage::Close(_) => return false,
+            _ => (),
+        }
+        true
+    }
+}
+
+impl Stream for NotificationStream {
+    type Item = Notification;
+    type Error = APIError;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(notification) = self.pending.pop_front() {
+                return Ok(Async::Ready(Some(notification)));
+            }
+            let step = match self.state {
+                StreamState::Connecting(ref mut connect) => match connect.poll() {
+                    Ok(Async::Ready((socket, _))) => Step::Connected(socket),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => {
+                        warn!("Failed to connect to the notifications: {:?}", e);
+                        Step::Reconnect
+                    }
+                },
+                StreamState::Connected(ref mut socket) => match socket.poll() {
+                    Ok(Async::Ready(Some(message))) => Step::Message(message),
+                    Ok(Async::Ready(None)) => Step::Reconnect,
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => {
+                        warn!("Lost the connection to the notifications: {:?}", e);
+                        Step::Reconnect
+                    }
+                },
+                StreamState::Waiting(ref mut timeout) => match timeout.poll()? {
+                    Async::Ready(()) => Step::Connect,
+                    Async::NotReady => return Ok(Async::NotReady),
+                },
+            };
+            match step {
+                Step::Connect => {
+                    self.state = NotificationStream::connecting(self.url.as_str(), &self.handle)
+                }
+                Step::Connected(socket) => self.state = StreamState::Connected(socket),
+                Step::Reconnect => self.state = self.waiting()?,
+                Step::Message(message) => {
+                    if !self.on_message(message) {
+                        self.state = self.waiting()?
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playing_notification() {
+        let json = r##"{"NotificationContainer":{"type":"playing","size":1,
+        "PlaySessionStateNotification":[{"sessionKey":"12","clientIdentifier":"player1",
+        "guid":"","ratingKey":"444","url":"","key":"/library/metadata/444","viewOffset":60000,
+        "playQueueItemID":21,"state":"paused"}]}}"##;
+        let notifications = Notification::parse(json).unwrap();
+        assert_eq!(notifications.len(), 1);
+        match notifications[0] {
+            Notification::Playing(ref state) => {
+                assert_eq!(state.session_key, "12");
+                assert_eq!(state.state, "paused");
+                assert_eq!(state.view_offset, 60000);
+            }
+            ref other => panic!("unexpected notification {:?}", other),
+        }
+    }
+
+    #[test]
+    fn timeline_and_activity_notifications() {
+        let json = r##"{"NotificationContainer":{"type":"timeline","size":2,"TimelineEntry":[
+        {"identifier":"com.plexapp.plugins.library","sectionID":1,"itemID":444,"type":1,
+        "title":"First Movie","state":5,"metadataState":"created","updatedAt":1511735264},
+        {"identifier":"com.plexapp.plugins.library","sectionID":"2","itemID":"15","type":10,
+        "state":9,"updatedAt":1511735264}]}}"##;
+        let notifications = Notification::parse(json).unwrap();
+        assert_eq!(notifications.len(), 2);
+        match notifications[0] {
+            Notification::Timeline(ref entry) => assert_eq!(entry.section_id, "1"),
+            ref other => panic!("unexpected notification {:?}", other),
+        }
+
+        let json = r##"{"NotificationContainer":{"type":"activity","size":1,
+        "ActivityNotification":[{"event":"updated","uuid":"abc","Activity":{"uuid":"abc",
+        "type":"library.update.section","cancellable":true,"userID":1,"title":"Scanning Movies",
+        "subtitle":"First Movie","progress":42}}]}}"##;
+        match Notification::parse(json).unwrap()[0] {
+            Notification::Activity(ref a) => {
+                assert_eq!(a.activity.progress, 42);
+                assert!(a.activity.cancellable);
+            }
+            ref other => panic!("unexpected notification {:?}", other),
+        }
+    }
+
+    #[test]
+    fn transcode_and_reachability_notifications() {
+        let json = r##"{"NotificationContainer":{"type":"transcodeSession.update","size":1,
+        "TranscodeSession":[{"key":"xyz","throttled":true,"complete":false,"progress":12.5,
+        "speed":3.1,"duration":2170971,"context":"streaming","videoDecision":"transcode",
+        "audioDecision":"copy","transcodeHwRequested":false}]}}"##;
+        match Notification::parse(json).unwrap()[0] {
+            Notification::TranscodeSessionUpdate(ref t) => assert!(t.throttled),
+            ref other => panic!("unexpected notification {:?}", other),
+        }
+
+        let json = r##"{"NotificationContainer":{"type":"reachability","size":1,
+        "ReachabilityNotification":[{"reachability":false}]}}"##;
+        assert_eq!(
+            Notification::parse(json).unwrap(),
+            vec![
+                Notification::Reachability(ReachabilityNotification {
+                    reachability: false,
+                }),
+            ]
+        );
+
+        let json = r##"{"NotificationContainer":{"type":"backgroundProcessingQueue","size":1}}"##;
+        assert_eq!(
+            Notification::parse(json).unwrap(),
+            vec![Notification::Other("backgroundProcessingQueue".to_string())]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use errors::APIError;
+use std::collections::HashMap;
+use url::form_urlencoded::Serializer;
+
+/// The type a server setting is declared with
+#[derive(Debug, PartialEq, Clone)]
+pub enum SettingType {
+    Bool,
+    Int,
+    Double,
+    Text,
+}
+
+impl SettingType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            SettingType::Bool => "bool",
+            SettingType::Int => "int",
+            SettingType::Double => "double",
+            SettingType::Text => "text",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<SettingType> {
+        match s {
+            "bool" => Some(SettingType::Bool),
+            "int" => Some(SettingType::Int),
+            "double" => Some(SettingType::Double),
+            "text" => Some(SettingType::Text),
+            _ => None,
+        }
+    }
+}
+
+/// Typed value of a server setting
+#[derive(Debug, PartialEq, Clone)]
+pub enum SettingValue {
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    Text(String),
+}
+
+impl SettingValue {
+    /// Parses the raw value according to the type of the setting
+    pub fn parse(setting_type: &SettingType, raw: &str) -> Result<SettingValue, APIError> {
+        let err = || APIError::ParseError(format!("Invalid {} value: {}", setting_type.as_str(), raw));
+        match *setting_type {
+            SettingType::Bool => match raw {
+                "true" | "1" => Ok(SettingValue::Bool(true)),
+                "false" | "0" => Ok(SettingValue::Bool(false)),
+                _ => Err(err()),
+            },
+            SettingType::Int => raw.parse()
+                .map(SettingValue::Int)
+                .map_err(|_| err()),
+            SettingType::Double => raw.parse()
+                .map(SettingValue::Double)
+                .map_err(|_| err()),
+            SettingType::Text => Ok(SettingValue::Text(raw.to_string())),
+        }
+    }
+
+    /// The value as the server expects it in a preferences update
+    pub fn format(&self) -> String {
+        match *self {
+            SettingValue::Bool(v) => v.to_string(),
+            SettingValue::Int(v) => v.to_string(),
+            SettingValue::Double(v) => v.to_string(),
+            SettingValue::Text(ref v) => v.clone(),
+        }
+    }
+
+    fn is_type(&self, setting_type: &SettingType) -> bool {
+        match (self, setting_type) {
+            (&SettingValue::Bool(_), &SettingType::Bool)
+            | (&SettingValue::Int(_), &SettingType::Int)
+            | (&SettingValue::Int(_), &SettingType::Double)
+            | (&SettingValue::Double(_), &SettingType::Double)
+            | (&SettingValue::Text(_), &SettingType::Text) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<bool> for SettingValue {
+    fn from(v: bool) -> Self {
+        SettingValue::Bool(v)
+    }
+}
+
+impl From<i64> for SettingValue {
+    fn from(v: i64) -> Self {
+        SettingValue::Int(v)
+    }
+}
+
+impl From<f64> for SettingValue {
+    fn from(v: f64) -> Self {
+        SettingValue::Double(v)
+    }
+}
+
+impl<'a> From<&'a str> for SettingValue {
+    fn from(v: &'a str) -> Self {
+        SettingValue::Text(v.to_string())
+    }
+}
+
+impl From<String> for SettingValue {
+    fn from(v: String) -> Self {
+        SettingValue::Text(v)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SettingContainer {
+    pub size: String,
+    #[serde(rename = "Setting", default)]
+    pub settings: Vec<Setting>,
+}
+
+/// A single server preference as listed by `/:/prefs`
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Setting {
+    pub id: String,
+    pub label: String,
+    pub summary: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub default: String,
+    pub value: String,
+    pub hidden: String,
+    pub advanced: String,
+    pub group: String,
+    pub enum_values: String,
+}
+
+impl Setting {
+    pub const PATH: &'static str = "/:/prefs";
+
+    pub fn setting_type(&self) -> Option<SettingType> {
+        SettingType::from_str(self.type_.as_str())
+    }
+
+    pub fn value(&self) -> Result<SettingValue, APIError> {
+        SettingValue::parse(&self.checked_type()?, self.value.as_str())
+    }
+
+    pub fn default_value(&self) -> Result<SettingValue, APIError> {
+        SettingValue::parse(&self.checked_type()?, self.default.as_str())
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        "1".eq(&self.hidden)
+    }
+
+    pub fn is_advanced(&self) -> bool {
+        "1".eq(&self.advanced)
+    }
+
+    /// The allowed values and their labels, empty if any value is allowed.
+    ///
+    /// Plex advertises them either as `value:label|value:label` or as `value|value`.
+    pub fn enum_values(&self) -> Vec<(String, String)> {
+        self.enum_values
+            .split('|')
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let mut split = p.splitn(2, ':');
+                let value = split.next().unwrap_or("").to_string();
+                let label = split.next().map(|l| l.to_string()).unwrap_or(value.clone());
+                (value, label)
+            })
+            .collect()
+    }
+
+    /// Checks that the value has the type of the setting and is one of its advertised values
+    pub fn validate(&self, value: &SettingValue) -> Result<String, APIError> {
+        let setting_type = self.checked_type()?;
+        if !value.is_type(&setting_type) {
+            return Err(APIError::InvalidSetting(format!(
+                "{} expects a {} value, got {:?}",
+                self.id,
+                setting_type.as_str(),
+                value
+            )));
+        }
+        let formatted = value.format();
+        let allowed = self.enum_values();
+        if !allowed.is_empty() && !allowed.iter().any(|&(ref v, _)| v.eq(&formatted)) {
+            return Err(APIError::InvalidSetting(format!(
+                "{} is not a valid value for {}, expected one of {:?}",
+                formatted,
+                self.id,
+                allowed.iter().map(|&(ref v, _)| v.as_str()).collect::<Vec<_>>()
+            )));
+        }
+        Ok(formatted)
+    }
+
+    fn checked_type(&self) -> Result<SettingType, APIError> {
+        self.setting_type().ok_or_else(|| {
+            APIError::ParseError(format!("Unknown type {} of setting {}", self.type_, self.id))
+        })
+    }
+}
+
+/// Validates all values against the advertised settings and builds the query of the update
+pub fn update_query(settings: &[Setting], values: &[(String, SettingValue)]) -> Result<String, APIError> {
+    let settings = settings
+        .iter()
+        .map(|s| (s.id.as_str(), s))
+        .collect::<HashMap<_, _>>();
+    let mut query = Serializer::new(String::new());
+    for &(ref id, ref value) in values {
+        let setting = settings
+            .get(id.as_str())
+            .ok_or_else(|| APIError::InvalidSetting(format!("Unknown setting {}", id)))?;
+        query.append_pair(id.as_str(), setting.validate(value)?.as_str());
+    }
+    Ok(query.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    const PREFS: &'static str = fixture!("prefs.xml");
+
+    #[test]
+    fn settings_deserialize() {
+        let container: Result<SettingContainer, Error> = deserialize(PREFS.as_bytes());
+        assert!(container.is_ok());
+        let settings = container.unwrap().settings;
+        assert_eq!(settings.len(), 3);
+        assert_eq!(settings[0].value().unwrap(), SettingValue::Text("Mock".to_string()));
+        assert_eq!(settings[1].value().unwrap(), SettingValue::Bool(false));
+        assert_eq!(settings[1].default_value().unwrap(), SettingValue::Bool(true));
+        assert_eq!(settings[2].value().unwrap(), SettingValue::Int(0));
+        assert_eq!(settings[2].enum_values().len(), 4);
+        assert_eq!(settings[2].enum_values()[3].1, "make my CPU hurt");
+    }
+
+    #[test]
+    fn settings_update_query() {
+        let settings: SettingContainer = deserialize(PREFS.as_bytes()).unwrap();
+        let settings = settings.settings;
+
+        let query = update_query(
+            &settings,
+            &[
+                ("FriendlyName".to_string(), "My Server".into()),
+                ("logDebug".to_string(), true.into()),
+                ("TranscoderQuality".to_string(), 3i64.into()),
+            ],
+        ).unwrap();
+        assert_eq!(query, "FriendlyName=My+Server&logDebug=true&TranscoderQuality=3");
+
+        assert!(update_query(&settings, &[("TranscoderQuality".to_string(), 4i64.into())]).is_err());
+        assert!(update_query(&settings, &[("logDebug".to_string(), "yes".into())]).is_err());
+        assert!(update_query(&settings, &[("Unknown".to_string(), true.into())]).is_err());
+    }
+}
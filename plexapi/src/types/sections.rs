@@ -1,14 +1,21 @@
 use types::library::PlexLibrary;
 use types::media::video::*;
 use types::media::audio::*;
+use types::media::photo::{Photo, PhotoContainer, PhotoItem};
+use types::media::show::{PlexShow, ShowContainer};
+use types::media::common::MediaType;
+use types::server::Directory;
 use types::settings::X_PLEX_CONTAINER_SIZE;
 use types::device::Connection;
 use types::{PlexToken, PlexTokenProvider};
 use types::filter::*;
+use types::hub::{Hub, HubContainer};
+use types::feature::Feature;
 use client::{PlexClient, PlexClientProvider};
 use errors::APIError;
-use futures::Future;
+use futures::{future, Future};
 use std::ops::FnMut;
+use url::form_urlencoded::Serializer;
 use std::cmp::min;
 use futures::future::{loop_fn, Loop};
 use std::rc::Rc;
@@ -26,6 +33,7 @@ pub struct PlexLibSection<'a> {
     pub inner: Section,
     client: Rc<PlexClient<'a>>,
     conn: Connection,
+    version: String,
 }
 
 impl<'a> PlexClientProvider<'a> for PlexLibSection<'a> {
@@ -35,11 +43,17 @@ impl<'a> PlexClientProvider<'a> for PlexLibSection<'a> {
 }
 
 impl<'a> PlexLibSection<'a> {
-    pub fn new(inner: Section, client: Rc<PlexClient<'a>>, conn: Connection) -> Self {
+    pub fn new(
+        inner: Section,
+        client: Rc<PlexClient<'a>>,
+        conn: Connection,
+        version: String,
+    ) -> Self {
         PlexLibSection {
             inner,
             client,
             conn,
+            version,
         }
     }
 
@@ -52,6 +66,14 @@ impl<'a> PlexLibSection<'a> {
         let url = self.format_path("all");
     }
 
+    /// Number of items in the section, without fetching them
+    pub fn item_count(&self) -> impl Future<Item = usize, Error = APIError> {
+        let url = format!("{}{}all", self.conn.endpoint(), self.inner.path());
+        self.client
+            .get_xml_container::<ContainerSize>(url.as_str(), 0, 0)
+            .map(|container| container.total())
+    }
+
     pub fn into<T>(self) -> Option<T>
     where
         T: From<PlexLibSection<'a>> + LibrarySection<'a>,
@@ -70,28 +92,6 @@ impl<'a> PlexLibSection<'a> {
     }
 }
 
-//let fetch_items = |(lib, elements):
-//(Self, Vec<Self::Content>)|
-//{
-////                    let x = lib.fetch("");
-////                    Box::new(lib.fetch_container("", elements.len(), 200usize).and_then(|items| {
-//////                    let fetched_size = items.len();
-//////                    elements.extend(items.into_iter());
-////
-//////                    match max_results {
-//////                        Some(s) => {
-//////                            if elements.len() >= s { return Ok(Loop::Break(elements)); }
-//////                        }
-//////                        _ => ()
-//////                    };
-//////                    if fetched_size < ssize {
-//////                        Ok(Loop::Break(elements))
-//////                    } else {
-////                        Ok(Loop::Break(Vec::new()))
-//////                    }
-////                    }))
-//};
-
 ///
 pub trait LibrarySection<'a>: Clone + Sized + PlexClientProvider<'a> {
     type Content: 'a;
@@ -129,48 +129,66 @@ pub trait LibrarySection<'a>: Clone + Sized + PlexClientProvider<'a> {
         self.fetch(url.as_str())
     }
 
-    // -> Box<Future<Item=Vec<Self::Content>, Error=Self::Error>>
-    fn search(&'a self, filter: Vec<Self::Filter>, max_results: Option<usize>) {
-        let container_size = min(
-            max_results.unwrap_or(X_PLEX_CONTAINER_SIZE),
-            X_PLEX_CONTAINER_SIZE,
+    /// The hubs the server curates for the section, eg. "Recently Added Movies"
+    fn hubs(&self) -> Box<Future<Item = Vec<Hub>, Error = APIError> + 'a> {
+        let url = format!(
+            "{}{}/{}",
+            self.connection().endpoint(),
+            HubContainer::SECTIONS,
+            self.key()
         );
-        let query = match filter.is_empty() {
-            false => format!(
-                "?{}",
-                filter
-                    .iter()
-                    .map(|f| f.format())
-                    .filter(|p| !p.is_empty())
-                    .collect::<Vec<_>>()
-                    .join("&")
-            ),
-            _ => "".to_string(),
-        };
+        let client = Rc::clone(self.client());
+        Box::new(
+            future::result(Feature::Hubs.require(self.server_version())).and_then(move |_| {
+                client
+                    .get_xml::<HubContainer>(url.as_str())
+                    .map(|container| container.hubs)
+            }),
+        )
+    }
+
+    /// Items whose title contains `title`, narrowed down by the filters.
+    ///
+    /// Fetches pages of `X_PLEX_CONTAINER_SIZE` items until `max_results` are found
+    /// or the section has no more matches, see `fetch_pages`.
+    fn search(
+        &self,
+        title: Option<&str>,
+        filter: Vec<Self::Filter>,
+        max_results: Option<usize>,
+    ) -> Box<Future<Item = Vec<Self::Content>, Error = Self::Error> + 'a>
+    where
+        Self: 'a,
+    {
+        let mut query = Serializer::new(String::new());
+        if let Some(t) = Self::content_type() {
+            query.append_pair("type", t.id().to_string().as_str());
+        }
+        if let Some(t) = title {
+            query.append_pair("title", t);
+        }
+        // filters are already encoded
+        let query = ::std::iter::once(query.finish())
+            .chain(filter.iter().map(|f| f.format()))
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join("&");
         let url = format!(
-            "{}{}/{}/all{}",
+            "{}{}/{}/all?{}",
             self.connection().endpoint(),
             PlexLibrary::SECTIONS,
             self.key(),
             query
         );
+        let section = self.clone();
+        fetch_pages(max_results, move |start, size| {
+            section.fetch_container(url.as_str(), start, size)
+        })
+    }
 
-        println!("{}", url);
-        //
-        // let x: Box<Future<Item=Vec<Self::Content>, Error=Self::Error>> =
-        //     Box::new(loop_fn((self.clone(), Vec::new()),
-        //                     |(s, elements)| {
-        //                         if elements.is_empty() {
-        //                             return Ok(Loop::Break(elements));
-        //                         } else {
-        //                             s.clone()
-        // .fetch_container("", elements.len(), 200usize).and_then(|items| {
-        //                                 Ok(())
-        //                             });
-        //                         }
-        //                         Ok(Loop::Continue((s, elements)))
-        //                     }));
-        // x
+    /// The type of the items `search` asks for, if the section holds more than one
+    fn content_type() -> Option<MediaType> {
+        None
     }
 
     /// need to be implemented in order to support custom deserialization
@@ -180,20 +198,15 @@ pub trait LibrarySection<'a>: Clone + Sized + PlexClientProvider<'a> {
         url: &str,
         start: usize,
         max: usize,
-    ) -> Box<Future<Item = Vec<Self::Content>, Error = Self::Error> + 'a>;
+    ) -> Box<Future<Item = Page<Self::Content>, Error = Self::Error> + 'a>;
 
     fn section_type() -> SectionType;
     fn connection(&self) -> &Connection;
     fn key(&self) -> String;
+    /// version of the server the section belongs to
+    fn server_version(&self) -> &str;
 }
 
-//fn looper<'a, T: LibrarySection + 'static>((lib, elements):(T, Vec<T::Content>))
-//-> Box<Future<Item=Loop<Vec<T::Content>, (Box<T>, Vec<T::Content>)>, Error=T::Error>> {
-//    Box::new(lib.fetch_container("", elements.len(), 200usize).and_then(|items| {
-//        Ok(Loop::Break(elements))
-//    }))
-//}
-
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Sections {
@@ -273,6 +286,86 @@ impl Section {
     }
 }
 
+/// One page of a container that is fetched in parts
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// `totalSize` of the container, servers only send it for paged requests
+    pub total: Option<usize>,
+    /// `offset` of the page, the start the server actually used
+    pub offset: Option<usize>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total_size: &str, offset: &str) -> Self {
+        Page {
+            items,
+            total: total_size.parse().ok(),
+            offset: offset.parse().ok(),
+        }
+    }
+}
+
+/// Fetches the pages of a container one after another.
+///
+/// Stops once the `totalSize` of the container is reached, a page is empty or starts at another
+/// offset than requested, or `max_results` items are collected. Without a `totalSize` a short
+/// page is the last one.
+pub(crate) fn fetch_pages<'a, T, E, F>(
+    max_results: Option<usize>,
+    fetch: F,
+) -> Box<Future<Item = Vec<T>, Error = E> + 'a>
+where
+    T: 'a,
+    E: 'a,
+    F: Fn(usize, usize) -> Box<Future<Item = Page<T>, Error = E> + 'a> + 'a,
+{
+    let page_size = min(
+        max_results.unwrap_or(X_PLEX_CONTAINER_SIZE),
+        X_PLEX_CONTAINER_SIZE,
+    );
+    let limit = max_results.unwrap_or(usize::max_value());
+    Box::new(loop_fn(Vec::new(), move |mut items: Vec<T>| {
+        let start = items.len();
+        fetch(start, page_size).map(move |page| {
+            // a server that ignores the start would hand out the same items again
+            if page.offset.map_or(false, |offset| offset != start) {
+                return Loop::Break(items);
+            }
+            let fetched = page.items.len();
+            items.extend(page.items);
+            let exhausted = match page.total {
+                Some(total) => items.len() >= total,
+                None => fetched < page_size,
+            };
+            if fetched == 0 || exhausted || items.len() >= limit {
+                items.truncate(limit);
+                Loop::Break(items)
+            } else {
+                Loop::Continue(items)
+            }
+        })
+    }))
+}
+
+/// Only the size attributes of a container, the content is skipped
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContainerSize {
+    pub size: String,
+    pub total_size: String,
+}
+
+impl ContainerSize {
+    /// Size of the whole container, servers only send `totalSize` for paged requests
+    pub fn total(&self) -> usize {
+        self.total_size
+            .parse()
+            .or_else(|_| self.size.parse())
+            .unwrap_or(0)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Location {
     id: String,
@@ -318,11 +411,13 @@ impl<'a> LibrarySection<'a> for MovieSection<'a> {
         url: &str,
         start: usize,
         max: usize,
-    ) -> Box<Future<Item = Vec<Self::Content>, Error = Self::Error> + 'a> {
+    ) -> Box<Future<Item = Page<Self::Content>, Error = Self::Error> + 'a> {
         Box::new(
             self.client()
                 .get_xml_container::<VideoContainer>(url, start, max)
-                .map(move |container| container.videos),
+                .map(move |container| {
+                    Page::new(container.videos, &container.total_size, &container.offset)
+                }),
         )
     }
 
@@ -330,6 +425,10 @@ impl<'a> LibrarySection<'a> for MovieSection<'a> {
         &self.inner.conn
     }
 
+    fn server_version(&self) -> &str {
+        self.inner.version.as_str()
+    }
+
     fn key(&self) -> String {
         self.inner.inner.key.clone()
     }
@@ -337,6 +436,10 @@ impl<'a> LibrarySection<'a> for MovieSection<'a> {
     fn section_type() -> SectionType {
         SectionType::Movie
     }
+
+    fn content_type() -> Option<MediaType> {
+        Some(MediaType::Movie)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -349,6 +452,36 @@ impl<'a> MusicSection<'a> {
     pub fn albums(&self) -> impl Future<Item = Vec<PlexAlbum<'a>>, Error = APIError> {
         self.all()
     }
+
+    /// All artists of the section, fetched page by page
+    pub fn artists(&self) -> impl Future<Item = Vec<PlexArtist<'a>>, Error = APIError> + 'a {
+        let url = self.all_of_type(MediaType::Artist);
+        let conn = self.connection().clone();
+        let client = Rc::clone(self.client());
+        fetch_pages(None, move |start, size| {
+            Box::new(artist_page(&client, &conn, url.as_str(), start, size))
+        })
+    }
+
+    /// All tracks of the section regardless of their album, fetched page by page
+    pub fn tracks(&self) -> impl Future<Item = Vec<PlexTrack<'a>>, Error = APIError> + 'a {
+        let url = self.all_of_type(MediaType::Track);
+        let conn = self.connection().clone();
+        let client = Rc::clone(self.client());
+        fetch_pages(None, move |start, size| {
+            Box::new(track_page(&client, &conn, url.as_str(), start, size))
+        })
+    }
+
+    fn all_of_type(&self, media_type: MediaType) -> String {
+        format!(
+            "{}{}/{}/all?type={}",
+            self.connection().endpoint(),
+            PlexLibrary::SECTIONS,
+            self.key(),
+            media_type.id()
+        )
+    }
 }
 
 impl<'a> LibrarySection<'a> for MusicSection<'a> {
@@ -383,18 +516,19 @@ impl<'a> LibrarySection<'a> for MusicSection<'a> {
         url: &str,
         start: usize,
         max: usize,
-    ) -> Box<Future<Item = Vec<Self::Content>, Error = Self::Error> + 'a> {
+    ) -> Box<Future<Item = Page<Self::Content>, Error = Self::Error> + 'a> {
         let client = Rc::clone(&self.inner.client);
         let conn = self.connection().clone();
         Box::new(
             client
                 .get_xml_container::<AlbumContainer>(url, start, max)
                 .map(move |container| {
-                    container
+                    let albums = container
                         .albums
                         .into_iter()
                         .map(|album| PlexAlbum::new(album, conn.clone(), Rc::clone(&client)))
-                        .collect::<Vec<_>>()
+                        .collect::<Vec<_>>();
+                    Page::new(albums, &container.total_size, &container.offset)
                 }),
         )
     }
@@ -403,6 +537,10 @@ impl<'a> LibrarySection<'a> for MusicSection<'a> {
         &self.inner.conn
     }
 
+    fn server_version(&self) -> &str {
+        self.inner.version.as_str()
+    }
+
     fn key(&self) -> String {
         self.inner.inner.key.clone()
     }
@@ -410,4 +548,217 @@ impl<'a> LibrarySection<'a> for MusicSection<'a> {
     fn section_type() -> SectionType {
         SectionType::Music
     }
+
+    fn content_type() -> Option<MediaType> {
+        Some(MediaType::Album)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowSection<'a> {
+    inner: PlexLibSection<'a>,
+}
+plex_lib_section!(ShowSection);
+
+impl<'a> ShowSection<'a> {
+    pub fn shows(&self) -> impl Future<Item = Vec<PlexShow<'a>>, Error = APIError> {
+        self.all()
+    }
+}
+
+impl<'a> LibrarySection<'a> for ShowSection<'a> {
+    type Content = PlexShow<'a>;
+    type Error = APIError;
+    type Filter = ShowLibraryFilter;
+
+    fn fetch(&self, url: &str) -> Box<Future<Item = Vec<Self::Content>, Error = Self::Error> + 'a> {
+        let client = Rc::clone(&self.inner.client);
+        let conn = self.connection().clone();
+        Box::new(client.get_xml::<ShowContainer>(url).map(move |container| {
+            container
+                .shows
+                .into_iter()
+                .map(|show| PlexShow::new(show, conn.clone(), Rc::clone(&client)))
+                .collect::<Vec<_>>()
+        }))
+    }
+
+    fn fetch_container(
+        &self,
+        url: &str,
+        start: usize,
+        max: usize,
+    ) -> Box<Future<Item = Page<Self::Content>, Error = Self::Error> + 'a> {
+        let client = Rc::clone(&self.inner.client);
+        let conn = self.connection().clone();
+        Box::new(
+            client
+                .get_xml_container::<ShowContainer>(url, start, max)
+                .map(move |container| {
+                    let shows = container
+                        .shows
+                        .into_iter()
+                        .map(|show| PlexShow::new(show, conn.clone(), Rc::clone(&client)))
+                        .collect::<Vec<_>>();
+                    Page::new(shows, &container.total_size, &container.offset)
+                }),
+        )
+    }
+
+    fn connection(&self) -> &Connection {
+        &self.inner.conn
+    }
+
+    fn server_version(&self) -> &str {
+        self.inner.version.as_str()
+    }
+
+    fn key(&self) -> String {
+        self.inner.inner.key.clone()
+    }
+
+    fn section_type() -> SectionType {
+        SectionType::Show
+    }
+
+    fn content_type() -> Option<MediaType> {
+        Some(MediaType::Show)
+    }
+}
+
+/// The years that have photos, eg. the entries of `/library/sections/4/year`
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct YearContainer {
+    pub size: String,
+    #[serde(rename = "Directory", default)]
+    pub years: Vec<Directory>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhotoSection<'a> {
+    inner: PlexLibSection<'a>,
+}
+plex_lib_section!(PhotoSection);
+
+impl<'a> PhotoSection<'a> {
+    /// The albums, photos and clips at the top of the section
+    pub fn items(&self) -> impl Future<Item = Vec<PhotoItem<'a>>, Error = APIError> {
+        self.all()
+    }
+
+    /// The years photos were taken in, newest first
+    pub fn years(&self) -> impl Future<Item = Vec<u16>, Error = APIError> {
+        let url = format!(
+            "{}{}/{}/year",
+            self.connection().endpoint(),
+            PlexLibrary::SECTIONS,
+            self.key()
+        );
+        self.client()
+            .get_xml::<YearContainer>(url.as_str())
+            .map(|container| {
+                let mut years = container
+                    .years
+                    .iter()
+                    .filter_map(|y| y.title.parse().ok())
+                    .collect::<Vec<u16>>();
+                years.sort_by(|a, b| b.cmp(a));
+                years
+            })
+    }
+
+    /// The photos taken in the year, or only in a month of it, regardless of their album.
+    ///
+    /// Fails with `APIError::InvalidArgument` if the month is not within 1 to 12, or if the
+    /// year is the last one a `u16` can hold.
+    pub fn photos_taken_in(
+        &self,
+        year: u16,
+        month: Option<u8>,
+    ) -> impl Future<Item = Vec<Photo>, Error = APIError> + 'a {
+        let next_year = year
+            .checked_add(1)
+            .ok_or_else(|| APIError::InvalidArgument(format!("year {} is too large", year)));
+        let range = next_year.and_then(|next_year| match month {
+            Some(m) if m < 1 || m > 12 => Err(APIError::InvalidArgument(format!(
+                "month {} is not within 1 to 12",
+                m
+            ))),
+            Some(12) => Ok((format!("{}-12-01", year), format!("{}-01-01", next_year))),
+            Some(m) => Ok((
+                format!("{}-{:02}-01", year, m),
+                format!("{}-{:02}-01", year, m + 1),
+            )),
+            _ => Ok((format!("{}-01-01", year), format!("{}-01-01", next_year))),
+        });
+        let client = Rc::clone(self.client());
+        let base = format!(
+            "{}{}/{}/all",
+            self.connection().endpoint(),
+            PlexLibrary::SECTIONS,
+            self.key()
+        );
+        future::result(range).and_then(move |(after, before)| {
+            let query = [
+                format!("type={}", MediaType::Photo.id()),
+                PhotoLibraryFilter::TakenAfter(after).format(),
+                PhotoLibraryFilter::TakenBefore(before).format(),
+            ];
+            let url = format!("{}?{}", base, query.join("&"));
+            client
+                .get_xml::<PhotoContainer>(url.as_str())
+                .map(|container| container.photos)
+        })
+    }
+}
+
+impl<'a> LibrarySection<'a> for PhotoSection<'a> {
+    type Content = PhotoItem<'a>;
+    type Error = APIError;
+    type Filter = PhotoLibraryFilter;
+
+    fn fetch(&self, url: &str) -> Box<Future<Item = Vec<Self::Content>, Error = Self::Error> + 'a> {
+        let client = Rc::clone(&self.inner.client);
+        let conn = self.connection().clone();
+        Box::new(
+            client
+                .get_xml::<PhotoContainer>(url)
+                .map(move |container| container.into_items(&conn, &client)),
+        )
+    }
+
+    fn fetch_container(
+        &self,
+        url: &str,
+        start: usize,
+        max: usize,
+    ) -> Box<Future<Item = Page<Self::Content>, Error = Self::Error> + 'a> {
+        let client = Rc::clone(&self.inner.client);
+        let conn = self.connection().clone();
+        Box::new(
+            client
+                .get_xml_container::<PhotoContainer>(url, start, max)
+                .map(move |container| {
+                    let (total, offset) = (container.total_size.clone(), container.offset.clone());
+                    Page::new(container.into_items(&conn, &client), &total, &offset)
+                }),
+        )
+    }
+
+    fn connection(&self) -> &Connection {
+        &self.inner.conn
+    }
+
+    fn server_version(&self) -> &str {
+        self.inner.version.as_str()
+    }
+
+    fn key(&self) -> String {
+        self.inner.inner.key.clone()
+    }
+
+    fn section_type() -> SectionType {
+        SectionType::Photo
+    }
 }
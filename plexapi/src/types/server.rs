@@ -1,10 +1,34 @@
 use client::PlexClient;
 use types::library::{Library, PlexLibrary};
-use futures::Future;
+use futures::{future, Future};
 use errors::APIError;
+use types::activity::{Activity, ActivityContainer};
+use types::butler::{ButlerTask, ButlerTaskContainer, ButlerTaskName};
+use types::companion::{PlexPlayer, ServerClient, ServerClientContainer};
 use types::device::Connection;
+use types::feature::Feature;
+use types::history::{HistoryContainer, HistoryEntry, HistoryFilter};
+use types::hub::{Hub, HubContainer};
+use types::browse::BrowseNode;
+use types::preferences::{update_query, Setting, SettingContainer, SettingValue};
+use types::notification::NotificationStream;
+use types::session::{PlaybackSession, SessionContainer};
+use types::statistics::{BandwidthStatistics, ResourceStatistics, StatisticsTimespan};
+use types::transcode::{TranscodeSession, TranscodeSessionContainer};
+use types::updater::{Release, UpdaterStatus};
 use types::PlexTokenProvider;
+use types::sections::fetch_pages;
+use hyper::{Body, Client, Method, Request, Uri};
+use hyper::client::HttpConnector;
+use hyper_tls::HttpsConnector;
+use http::set_basic_plex_headers;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use std::str::FromStr;
+use tokio_core::reactor::Handle;
+use url::form_urlencoded::Serializer;
 
 #[derive(Debug)]
 pub struct PlexServer<'a> {
@@ -22,13 +46,412 @@ impl<'a> PlexServer<'a> {
         }
     }
 
+    /// What the server is able to do, derived from its root endpoint
+    pub fn capabilities(&self) -> Result<ServerCapabilities, APIError> {
+        self.inner.capabilities()
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Whether the server version is recent enough for the feature
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.require(feature).is_ok()
+    }
+
+    /// Fails with `APIError::UnsupportedVersion` if the server version is too old for the feature
+    pub fn require(&self, feature: Feature) -> Result<(), APIError> {
+        feature.require(self.inner.version())
+    }
+
     pub fn library(&self) -> impl Future<Item = PlexLibrary<'a>, Error = APIError> {
         let client = Rc::clone(&self.client);
         let url = self.conn.format_url(PlexLibrary::PATH, self.client.token());
         let conn = self.conn.clone();
-        client
-            .get_xml::<Library>(url.as_str())
-            .map(move |library| PlexLibrary::new(library, client, conn))
+        let version = self.inner.version().to_string();
+        future::result(self.require(Feature::Library)).and_then(move |_| {
+            client
+                .get_xml::<Library>(url.as_str())
+                .map(move |library| PlexLibrary::new(library, client, conn, version))
+        })
+    }
+
+    /// Everything that is currently played on the server
+    pub fn sessions(&self) -> impl Future<Item = Vec<PlaybackSession>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), SessionContainer::PATH);
+        future::result(self.require(Feature::Sessions)).and_then(move |_| {
+            client
+                .get_xml::<SessionContainer>(url.as_str())
+                .map(|container| container.into_sessions())
+        })
+    }
+
+    /// Stops the playback session with the id of its `Session`, the reason is shown to the
+    /// viewer. Only servers of plex pass subscribers allow this.
+    pub fn terminate_session(
+        &self,
+        session_id: &str,
+        reason: &str,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let query = Serializer::new(String::new())
+            .append_pair("sessionId", session_id)
+            .append_pair("reason", reason)
+            .finish();
+        let url = format!(
+            "{}{}?{}",
+            self.conn.endpoint(),
+            SessionContainer::TERMINATE,
+            query
+        );
+        future::result(self.require(Feature::TerminateSession))
+            .and_then(move |_| client.send(Method::Get, url.as_str()))
+    }
+
+    /// Stops the playback session, see `terminate_session`
+    pub fn terminate(
+        &self,
+        session: &PlaybackSession,
+        reason: &str,
+    ) -> Box<Future<Item = (), Error = APIError> + 'a> {
+        match session.session_id() {
+            Some(id) => Box::new(self.terminate_session(id, reason)),
+            _ => Box::new(future::err(APIError::MissingSessionId(
+                session.session_key.clone(),
+            ))),
+        }
+    }
+
+    /// Everything the transcoder is working on, including sessions that were already played
+    pub fn transcode_sessions(&self) -> impl Future<Item = Vec<TranscodeSession>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), TranscodeSessionContainer::PATH);
+        future::result(self.require(Feature::TranscodeSessions)).and_then(move |_| {
+            client
+                .get_xml::<TranscodeSessionContainer>(url.as_str())
+                .map(|container| container.sessions)
+        })
+    }
+
+    /// Stops the transcoder of a session, the playback session it belongs to is left alone
+    pub fn stop_transcode_session(&self, session_id: &str) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let query = Serializer::new(String::new())
+            .append_pair("session", session_id)
+            .finish();
+        let url = format!(
+            "{}{}?{}",
+            self.conn.endpoint(),
+            TranscodeSessionContainer::STOP,
+            query
+        );
+        future::result(self.require(Feature::TranscodeSessions))
+            .and_then(move |_| client.send(Method::Get, url.as_str()))
+    }
+
+    /// Stops the transcoder of the session, see `stop_transcode_session`
+    pub fn stop_transcode(
+        &self,
+        session: &TranscodeSession,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        self.stop_transcode_session(session.session_id())
+    }
+
+    /// The watch history, newest first.
+    ///
+    /// Pages are fetched until `max_results` entries are collected or the history is
+    /// exhausted, see `fetch_pages`.
+    pub fn history(
+        &self,
+        filters: Vec<HistoryFilter>,
+        max_results: Option<usize>,
+    ) -> Box<Future<Item = Vec<HistoryEntry>, Error = APIError> + 'a> {
+        let client = Rc::clone(&self.client);
+        let url = format!(
+            "{}{}?{}",
+            self.conn.endpoint(),
+            HistoryContainer::PATH,
+            HistoryContainer::query(&filters)
+        );
+        Box::new(
+            future::result(self.require(Feature::History)).and_then(move |_| {
+                fetch_pages(max_results, move |start, size| {
+                    Box::new(
+                        client
+                            .get_xml_container::<HistoryContainer>(url.as_str(), start, size)
+                            .map(|container| container.into_page()),
+                    )
+                })
+            }),
+        )
+    }
+
+    /// Long running tasks the server is working on, eg. library scans
+    pub fn activities(&self) -> impl Future<Item = Vec<Activity>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), ActivityContainer::PATH);
+        future::result(self.require(Feature::Activities)).and_then(move |_| {
+            client
+                .get_xml::<ActivityContainer>(url.as_str())
+                .map(|container| container.activities)
+        })
+    }
+
+    /// Cancels the activity with the uuid, only works for cancellable activities
+    pub fn cancel_activity(&self, uuid: &str) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}/{}", self.conn.endpoint(), ActivityContainer::PATH, uuid);
+        future::result(self.require(Feature::Activities))
+            .and_then(move |_| client.send(Method::Delete, url.as_str()))
+    }
+
+    /// The scheduled maintenance tasks of the server
+    pub fn butler_tasks(&self) -> impl Future<Item = Vec<ButlerTask>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), ButlerTaskContainer::PATH);
+        future::result(self.require(Feature::Butler)).and_then(move |_| {
+            client
+                .get_xml::<ButlerTaskContainer>(url.as_str())
+                .map(|container| container.tasks)
+        })
+    }
+
+    /// Runs the maintenance task right away instead of waiting for its schedule
+    pub fn start_butler_task(
+        &self,
+        task: &ButlerTaskName,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        self.butler_task_request(Method::Post, task)
+    }
+
+    pub fn stop_butler_task(
+        &self,
+        task: &ButlerTaskName,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        self.butler_task_request(Method::Delete, task)
+    }
+
+    fn butler_task_request(
+        &self,
+        method: Method,
+        task: &ButlerTaskName,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!(
+            "{}{}/{}",
+            self.conn.endpoint(),
+            ButlerTaskContainer::PATH,
+            task.as_str()
+        );
+        future::result(self.require(Feature::Butler))
+            .and_then(move |_| client.send(method, url.as_str()))
+    }
+
+    /// Bytes sent to the devices of all accounts, sampled per timespan
+    pub fn bandwidth_statistics(
+        &self,
+        timespan: StatisticsTimespan,
+    ) -> impl Future<Item = BandwidthStatistics, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!(
+            "{}{}?timespan={}",
+            self.conn.endpoint(),
+            BandwidthStatistics::PATH,
+            timespan.id()
+        );
+        future::result(self.require(Feature::Statistics))
+            .and_then(move |_| client.get_xml::<BandwidthStatistics>(url.as_str()))
+    }
+
+    /// Cpu and memory utilization of the host and the server, sampled per timespan
+    pub fn resource_statistics(
+        &self,
+        timespan: StatisticsTimespan,
+    ) -> impl Future<Item = ResourceStatistics, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!(
+            "{}{}?timespan={}",
+            self.conn.endpoint(),
+            ResourceStatistics::PATH,
+            timespan.id()
+        );
+        future::result(self.require(Feature::Statistics))
+            .and_then(move |_| client.get_xml::<ResourceStatistics>(url.as_str()))
+    }
+
+    /// Makes the server look for a new release, `download` fetches it right away
+    pub fn check_for_updates(&self, download: bool) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!(
+            "{}{}?download={}",
+            self.conn.endpoint(),
+            UpdaterStatus::CHECK,
+            download as u8
+        );
+        future::result(self.require(Feature::Updater))
+            .and_then(move |_| client.send(Method::Put, url.as_str()))
+    }
+
+    /// What the updater found during its last check
+    pub fn update_status(&self) -> impl Future<Item = UpdaterStatus, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), UpdaterStatus::PATH);
+        future::result(self.require(Feature::Updater))
+            .and_then(move |_| client.get_xml::<UpdaterStatus>(url.as_str()))
+    }
+
+    /// Checks for updates and returns the newest release that is newer than the server
+    pub fn available_update(&self) -> impl Future<Item = Option<Release>, Error = APIError> + 'a {
+        let status = self.update_status();
+        let current = self.inner.version().parse::<ServerVersion>();
+        self.check_for_updates(false)
+            .and_then(move |_| status)
+            .and_then(move |status| {
+                current.map(|current| status.available_release(&current).cloned())
+            })
+    }
+
+    /// Installs the downloaded release, the server restarts while doing so
+    pub fn install_update(&self) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), UpdaterStatus::APPLY);
+        future::result(self.require(Feature::Updater))
+            .and_then(move |_| client.send(Method::Put, url.as_str()))
+    }
+
+    /// Subscribes to the notifications of the server, the stream reconnects on its own
+    pub fn notifications(&self, handle: &Handle) -> Result<NotificationStream, APIError> {
+        self.require(Feature::Notifications)?;
+        let url = self.conn
+            .format_url(NotificationStream::PATH, self.client.token());
+        // same host and port, only the scheme differs
+        let url = match url.starts_with("https://") {
+            true => url.replacen("https://", "wss://", 1),
+            _ => url.replacen("http://", "ws://", 1),
+        };
+        NotificationStream::connect(url.as_str(), handle)
+    }
+
+    /// Searches all sections, or only the one with the `section_id`. The results are grouped in
+    /// hubs by their type, each hub has at most `limit` items.
+    pub fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        section_id: Option<&str>,
+    ) -> impl Future<Item = Vec<Hub>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!(
+            "{}{}?{}",
+            self.conn.endpoint(),
+            HubContainer::SEARCH,
+            HubContainer::search_query(query, limit, section_id)
+        );
+        future::result(self.require(Feature::HubSearch)).and_then(move |_| {
+            client
+                .get_xml::<HubContainer>(url.as_str())
+                .map(|container| container.into_hubs())
+        })
+    }
+
+    /// The hubs of the home screen, eg. "Continue Watching" or "Recently Added Movies", in the
+    /// order the server curates them
+    pub fn hubs(&self) -> impl Future<Item = Vec<Hub>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), HubContainer::HOME);
+        future::result(self.require(Feature::Hubs)).and_then(move |_| {
+            client
+                .get_xml::<HubContainer>(url.as_str())
+                .map(|container| container.hubs)
+        })
+    }
+
+    /// All items of a hub, hubs only carry the first few items if `has_more` is set
+    pub fn hub_items(&self, hub: Hub) -> impl Future<Item = Hub, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), hub.key);
+        future::result(self.require(Feature::Hubs)).and_then(move |_| {
+            client
+                .get_xml::<Hub>(url.as_str())
+                .map(move |items| hub.with_items_of(items))
+        })
+    }
+
+    /// The entries of the root of the server, eg. `library`, they can be followed with `browse`
+    pub fn root_directories(&self) -> &[Directory] {
+        self.inner.directories.as_slice()
+    }
+
+    /// Fetches the container at the path, eg. `/library` or `/system/plugins`
+    pub fn browse(&self, path: &str) -> impl Future<Item = BrowseNode, Error = APIError> {
+        let url = format!("{}{}", self.conn.endpoint(), path);
+        self.client.get_text(url.as_str()).map(BrowseNode::parse)
+    }
+
+    /// Follows a directory of the container at `parent`
+    pub fn browse_directory(
+        &self,
+        parent: &str,
+        directory: &Directory,
+    ) -> impl Future<Item = BrowseNode, Error = APIError> {
+        self.browse(directory.path(parent).as_str())
+    }
+
+    /// The players the server currently knows about
+    pub fn clients(&self) -> impl Future<Item = Vec<ServerClient>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), ServerClientContainer::PATH);
+        future::result(self.require(Feature::Clients)).and_then(move |_| {
+            client
+                .get_xml::<ServerClientContainer>(url.as_str())
+                .map(|container| container.clients)
+        })
+    }
+
+    /// Remote control for one of the `clients`, the server passes the commands on to the player
+    pub fn player(&self, client: &ServerClient) -> PlexPlayer<'a> {
+        PlexPlayer::new(
+            Rc::clone(&self.client),
+            self.conn.clone(),
+            client.machine_identifier.as_str(),
+        )
+    }
+
+    /// All settings of the server
+    pub fn preferences(&self) -> impl Future<Item = Vec<Setting>, Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let url = format!("{}{}", self.conn.endpoint(), Setting::PATH);
+        future::result(self.require(Feature::Preferences)).and_then(move |_| {
+            client
+                .get_xml::<SettingContainer>(url.as_str())
+                .map(|container| container.settings)
+        })
+    }
+
+    pub fn set_preference<V: Into<SettingValue>>(
+        &self,
+        id: &str,
+        value: V,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        self.set_preferences(vec![(id.to_string(), value.into())])
+    }
+
+    /// Updates several settings at once, the values are validated against the type and the
+    /// allowed values the server advertises before anything is sent
+    pub fn set_preferences(
+        &self,
+        values: Vec<(String, SettingValue)>,
+    ) -> impl Future<Item = (), Error = APIError> + 'a {
+        let client = Rc::clone(&self.client);
+        let endpoint = self.conn.endpoint();
+        self.preferences().and_then(move |settings| {
+            future::result(update_query(&settings, &values)).and_then(move |query| {
+                let url = format!("{}{}?{}", endpoint, Setting::PATH, query);
+                client.send(Method::Put, url.as_str())
+            })
+        })
     }
 }
 
@@ -87,12 +510,248 @@ pub struct Server {
     pub directories: Vec<Directory>,
 }
 
+impl Server {
+    pub fn friendly_name(&self) -> &str {
+        self.friendly_name.as_str()
+    }
+
+    pub fn machine_identifier(&self) -> &str {
+        self.machine_identifier.as_str()
+    }
+
+    pub fn version(&self) -> &str {
+        self.version.as_str()
+    }
+
+    pub fn platform(&self) -> &str {
+        self.platform.as_str()
+    }
+
+    pub fn capabilities(&self) -> Result<ServerCapabilities, APIError> {
+        Ok(ServerCapabilities {
+            version: self.version.parse()?,
+            platform: self.platform.clone(),
+            platform_version: self.platform_version.clone(),
+            transcoder_video: flag(&self.transcoder_video),
+            transcoder_audio: flag(&self.transcoder_audio),
+            transcoder_subtitles: flag(&self.transcoder_subtitles),
+            transcoder_photo: flag(&self.transcoder_photo),
+            sync: flag(&self.sync),
+            plex_pass: flag(&self.my_plex_subscription),
+            my_plex: flag(&self.my_plex),
+            mapping_state: MappingState::from_str(self.my_plex_mapping_state.as_str()),
+            hub_search: flag(&self.hub_search),
+            event_stream: flag(&self.event_stream),
+            updater: flag(&self.updater),
+        })
+    }
+}
+
+/// plex encodes booleans as "0" and "1"
+fn flag(s: &str) -> bool {
+    "1".eq(s)
+}
+
+/// Typed view on the features a server announces in its root endpoint
+#[derive(Debug, PartialEq, Clone)]
+pub struct ServerCapabilities {
+    pub version: ServerVersion,
+    pub platform: String,
+    pub platform_version: String,
+    pub transcoder_video: bool,
+    pub transcoder_audio: bool,
+    pub transcoder_subtitles: bool,
+    pub transcoder_photo: bool,
+    pub sync: bool,
+    /// whether the owner has an active plex pass subscription
+    pub plex_pass: bool,
+    pub my_plex: bool,
+    pub mapping_state: MappingState,
+    pub hub_search: bool,
+    pub event_stream: bool,
+    pub updater: bool,
+}
+
+impl ServerCapabilities {
+    pub fn supports(&self, feature: Feature) -> bool {
+        feature.is_supported_by(&self.version)
+    }
+}
+
+/// Whether the server is reachable through plex.tv
+#[derive(Debug, PartialEq, Clone)]
+pub enum MappingState {
+    Mapped,
+    Waiting,
+    Failed,
+    Unknown(String),
+}
+
+impl MappingState {
+    pub fn from_str(s: &str) -> MappingState {
+        match s {
+            "mapped" => MappingState::Mapped,
+            "waiting" => MappingState::Waiting,
+            "failed" => MappingState::Failed,
+            _ => MappingState::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// A plex server version like `1.7.2.3878-8088811b8`.
+///
+/// Follows semver for major, minor and patch, plex adds a build number and a revision hash.
+/// Versions are compared by their numeric parts only, the revision is a hash without order.
+#[derive(Debug, Clone, Default)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub build: u32,
+    pub revision: String,
+}
+
+impl ServerVersion {
+    pub fn new(major: u32, minor: u32, patch: u32, build: u32) -> Self {
+        ServerVersion {
+            major,
+            minor,
+            patch,
+            build,
+            revision: String::new(),
+        }
+    }
+
+    fn numbers(&self) -> (u32, u32, u32, u32) {
+        (self.major, self.minor, self.patch, self.build)
+    }
+}
+
+impl PartialEq for ServerVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.numbers() == other.numbers()
+    }
+}
+
+impl Eq for ServerVersion {}
+
+impl Hash for ServerVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numbers().hash(state)
+    }
+}
+
+impl Ord for ServerVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.numbers().cmp(&other.numbers())
+    }
+}
+
+impl PartialOrd for ServerVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for ServerVersion {
+    type Err = APIError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.trim().splitn(2, '-');
+        let numbers = split.next().unwrap_or("");
+        let revision = split.next().unwrap_or("").to_string();
+        let parts = numbers
+            .split('.')
+            .map(|p| p.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| APIError::ParseError(format!("Invalid server version: {}", s)))?;
+        if parts.is_empty() || parts.len() > 4 {
+            return Err(APIError::ParseError(format!("Invalid server version: {}", s)));
+        }
+        let part = |i: usize| parts.get(i).cloned().unwrap_or(0);
+        Ok(ServerVersion {
+            major: part(0),
+            minor: part(1),
+            patch: part(2),
+            build: part(3),
+            revision,
+        })
+    }
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}.{}", self.major, self.minor, self.patch, self.build)?;
+        if !self.revision.is_empty() {
+            write!(f, "-{}", self.revision)?;
+        }
+        Ok(())
+    }
+}
+
+/// Response of the `/identity` endpoint, which is available without a token
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Identity {
+    pub size: String,
+    pub claimed: String,
+    pub machine_identifier: String,
+    pub version: String,
+}
+
+impl Identity {
+    pub const PATH: &'static str = "/identity";
+
+    /// Asks the server who it is, no token is sent along
+    pub fn probe(
+        client: &Client<HttpsConnector<HttpConnector>, Body>,
+        conn: &Connection,
+    ) -> impl Future<Item = Identity, Error = APIError> {
+        let url = format!("{}{}", conn.endpoint(), Identity::PATH);
+        let mut request = Request::new(Method::Get, Uri::from_str(url.as_str()).unwrap());
+        set_basic_plex_headers(request.headers_mut());
+        PlexClient::from_xml_response::<Identity>(client.request(request))
+    }
+
+    pub fn is_claimed(&self) -> bool {
+        flag(&self.claimed)
+    }
+
+    pub fn version(&self) -> Result<ServerVersion, APIError> {
+        self.version.parse()
+    }
+}
+
+/// An entry of a container that leads to another container
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Directory {
-    count: String,
-    key: String,
-    title: String,
+    pub count: String,
+    /// relative to the container the directory is part of, unless it starts with `/`
+    pub key: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub summary: String,
+    pub thumb: String,
+    pub art: String,
+    /// set for directories that expect a search query
+    pub prompt: String,
+    pub search: String,
+}
+
+impl Directory {
+    /// The path of the directory, `parent` is the path of the container it is part of
+    pub fn path(&self, parent: &str) -> String {
+        match self.key.starts_with('/') {
+            true => self.key.clone(),
+            _ => format!("{}/{}", parent.trim_right_matches('/'), self.key),
+        }
+    }
+
+    pub fn is_search(&self) -> bool {
+        "1".eq(&self.search)
+    }
 }
 
 #[cfg(test)]
@@ -134,7 +793,7 @@ transcoderAudio="1" transcoderLyrics="1" transcoderPhoto="1"
     }
 
     #[test]
-    fn device_connect_deserialize() {
+    fn server_deserialize() {
         let xml = r##"<?xml version="1.0" encoding="UTF-8"?>
 <MediaContainer size="21" allowCameraUpload="0" allowChannelAccess="1"
 allowSharing="1" allowSync="0" backgroundProcessing="1"
@@ -165,4 +824,38 @@ transcoderAudio="1" transcoderLyrics="1" transcoderPhoto="1"
         let server: Result<Server, Error> = deserialize(xml.as_bytes());
         assert!(server.is_ok());
     }
+
+    #[test]
+    fn server_capabilities() {
+        let server: Server = deserialize(fixture!("server.xml").as_bytes()).unwrap();
+        let capabilities = server.capabilities().unwrap();
+        assert_eq!(capabilities.version, ServerVersion::new(1, 10, 1, 4602));
+        assert_eq!(capabilities.mapping_state, MappingState::Mapped);
+        assert!(capabilities.transcoder_video);
+        assert!(capabilities.sync);
+        assert!(capabilities.plex_pass);
+    }
+
+    #[test]
+    fn server_version_parse() {
+        let version: ServerVersion = "1.7.2.3878-8088811b8".parse().unwrap();
+        assert_eq!(version.build, 3878);
+        assert_eq!(version.revision, "8088811b8");
+        assert_eq!(version.to_string(), "1.7.2.3878-8088811b8");
+        assert!(version > ServerVersion::new(1, 7, 2, 0));
+        assert!(version < ServerVersion::new(1, 10, 0, 0));
+        assert_eq!("1.3".parse::<ServerVersion>().unwrap(), ServerVersion::new(1, 3, 0, 0));
+        assert!("1.x.2".parse::<ServerVersion>().is_err());
+        assert!("".parse::<ServerVersion>().is_err());
+    }
+
+    #[test]
+    fn identity_deserialize() {
+        let xml = fixture!("identity.xml");
+        let identity: Result<Identity, Error> = deserialize(xml.as_bytes());
+        assert!(identity.is_ok());
+        let identity = identity.unwrap();
+        assert!(identity.is_claimed());
+        assert_eq!(identity.version().unwrap(), ServerVersion::new(1, 10, 1, 4602));
+    }
 }
@@ -0,0 +1,208 @@
+use types::media::audio::Track;
+use types::media::video::Video;
+use types::transcode::TranscodeSession;
+
+/// Playback state of a player
+#[derive(Debug, PartialEq, Clone)]
+pub enum PlayerState {
+    Playing,
+    Paused,
+    Buffering,
+    Stopped,
+}
+
+impl PlayerState {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PlayerState::Playing => "playing",
+            PlayerState::Paused => "paused",
+            PlayerState::Buffering => "buffering",
+            PlayerState::Stopped => "stopped",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<PlayerState> {
+        match s {
+            "playing" => Some(PlayerState::Playing),
+            "paused" => Some(PlayerState::Paused),
+            "buffering" => Some(PlayerState::Buffering),
+            "stopped" => Some(PlayerState::Stopped),
+            _ => None,
+        }
+    }
+}
+
+/// The account that is playing a session
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionUser {
+    pub id: String,
+    pub thumb: String,
+    pub title: String,
+}
+
+/// The device a session is played on
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Player {
+    pub address: String,
+    pub device: String,
+    pub machine_identifier: String,
+    pub model: String,
+    pub platform: String,
+    pub platform_version: String,
+    pub product: String,
+    pub profile: String,
+    pub remote_public_address: String,
+    pub state: String,
+    pub title: String,
+    pub vendor: String,
+    pub version: String,
+    pub local: String,
+    pub relayed: String,
+    pub secure: String,
+    #[serde(rename = "userID")]
+    pub user_id: String,
+}
+
+impl Player {
+    pub fn state(&self) -> Option<PlayerState> {
+        PlayerState::from_str(self.state.as_str())
+    }
+
+    pub fn is_local(&self) -> bool {
+        "1".eq(&self.local)
+    }
+}
+
+/// Network details of a session
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Session {
+    pub id: String,
+    pub bandwidth: String,
+    pub location: String,
+}
+
+/// The sessions of the server, videos and tracks are deserialized separately so the order in
+/// which the server listed them across the two kinds is lost.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionContainer {
+    pub size: String,
+    #[serde(rename = "Video", default)]
+    pub videos: Vec<Video>,
+    #[serde(rename = "Track", default)]
+    pub tracks: Vec<Track>,
+}
+
+impl SessionContainer {
+    pub const PATH: &'static str = "/status/sessions";
+    pub const TERMINATE: &'static str = "/status/sessions/terminate";
+
+    /// All sessions, the videos come first followed by the tracks
+    pub fn into_sessions(self) -> Vec<PlaybackSession> {
+        let videos = self.videos.into_iter().map(PlaybackSession::from);
+        let tracks = self.tracks.into_iter().map(PlaybackSession::from);
+        videos.chain(tracks).collect()
+    }
+}
+
+/// The item that is being played
+#[derive(Debug, PartialEq, Clone)]
+pub enum SessionItem {
+    Video(Video),
+    Track(Track),
+}
+
+/// Something that is currently played on the server
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlaybackSession {
+    pub session_key: String,
+    pub user: SessionUser,
+    pub player: Player,
+    pub session: Option<Session>,
+    pub transcode_session: Option<TranscodeSession>,
+    /// playback position in milliseconds
+    pub view_offset: u64,
+    pub item: SessionItem,
+}
+
+impl PlaybackSession {
+    /// The session is transcoded instead of played directly
+    pub fn is_transcoding(&self) -> bool {
+        self.transcode_session.is_some()
+    }
+
+    /// The id that identifies the session when terminating it
+    pub fn session_id(&self) -> Option<&str> {
+        self.session.as_ref().map(|s| s.id.as_str())
+    }
+
+    pub fn title(&self) -> &str {
+        match self.item {
+            SessionItem::Video(ref v) => v.title.as_str(),
+            SessionItem::Track(ref t) => t.title.as_str(),
+        }
+    }
+}
+
+impl From<Video> for PlaybackSession {
+    fn from(mut video: Video) -> Self {
+        PlaybackSession {
+            session_key: video.session_key.clone(),
+            user: video.user.take().unwrap_or_default(),
+            player: video.player.take().unwrap_or_default(),
+            session: video.session.take(),
+            transcode_session: video.transcode_session.take(),
+            view_offset: video.view_offset.parse().unwrap_or(0),
+            item: SessionItem::Video(video),
+        }
+    }
+}
+
+impl From<Track> for PlaybackSession {
+    fn from(mut track: Track) -> Self {
+        PlaybackSession {
+            session_key: track.session_key.clone(),
+            user: track.user.take().unwrap_or_default(),
+            player: track.player.take().unwrap_or_default(),
+            session: track.session.take(),
+            transcode_session: track.transcode_session.take(),
+            view_offset: track.view_offset.parse().unwrap_or(0),
+            item: SessionItem::Track(track),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+    use types::transcode::StreamDecision;
+
+    #[test]
+    fn session_container_deserialize() {
+        let xml = fixture!("sessions.xml");
+        let container: Result<SessionContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let sessions = container.unwrap().into_sessions();
+        assert_eq!(sessions.len(), 2);
+
+        let movie = &sessions[0];
+        assert_eq!(movie.title(), "First Movie");
+        assert_eq!(movie.user.title, "owner");
+        assert_eq!(movie.player.state(), Some(PlayerState::Playing));
+        assert_eq!(movie.view_offset, 60000);
+        let transcode = movie.transcode_session.as_ref().unwrap();
+        assert!(transcode.is_throttled());
+        assert_eq!(transcode.video_decision(), Some(StreamDecision::Transcode));
+        assert_eq!(transcode.audio_decision(), Some(StreamDecision::Copy));
+        assert_eq!(transcode.speed(), Some(3.1));
+
+        let track = &sessions[1];
+        assert!(!track.is_transcoding());
+        assert_eq!(track.player.state(), Some(PlayerState::Paused));
+        assert_eq!(track.session.as_ref().unwrap().location, "wan");
+    }
+}
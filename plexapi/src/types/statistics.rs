@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+/// Granularity of the statistics samples
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StatisticsTimespan {
+    Seconds,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+}
+
+impl StatisticsTimespan {
+    /// The number plex identifies the timespan with in queries
+    pub fn id(&self) -> u8 {
+        match *self {
+            StatisticsTimespan::Hours => 1,
+            StatisticsTimespan::Days => 2,
+            StatisticsTimespan::Weeks => 3,
+            StatisticsTimespan::Months => 4,
+            StatisticsTimespan::Seconds => 6,
+        }
+    }
+}
+
+/// A device that shows up in the bandwidth statistics
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatisticsDevice {
+    pub id: String,
+    pub name: String,
+    pub platform: String,
+    pub client_identifier: String,
+    pub created_at: String,
+}
+
+/// An account that shows up in the bandwidth statistics
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StatisticsAccount {
+    pub id: String,
+    pub key: String,
+    pub name: String,
+    pub thumb: String,
+}
+
+/// Bytes sent to a device of an account during a timespan
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BandwidthSample {
+    #[serde(rename = "accountID")]
+    pub account_id: String,
+    #[serde(rename = "deviceID")]
+    pub device_id: String,
+    pub timespan: String,
+    pub at: String,
+    pub lan: String,
+    pub bytes: String,
+}
+
+impl BandwidthSample {
+    /// unix timestamp of the start of the sample
+    pub fn at(&self) -> Option<u64> {
+        self.at.parse().ok()
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.parse().unwrap_or(0)
+    }
+
+    pub fn is_lan(&self) -> bool {
+        "1".eq(&self.lan)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BandwidthStatistics {
+    pub size: String,
+    #[serde(rename = "Device", default)]
+    pub devices: Vec<StatisticsDevice>,
+    #[serde(rename = "Account", default)]
+    pub accounts: Vec<StatisticsAccount>,
+    #[serde(rename = "StatisticsBandwidth", default)]
+    pub samples: Vec<BandwidthSample>,
+}
+
+impl BandwidthStatistics {
+    pub const PATH: &'static str = "/statistics/bandwidth";
+
+    pub fn account(&self, id: &str) -> Option<&StatisticsAccount> {
+        self.accounts.iter().find(|a| a.id.eq(id))
+    }
+
+    pub fn device(&self, id: &str) -> Option<&StatisticsDevice> {
+        self.devices.iter().find(|d| d.id.eq(id))
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.samples.iter().map(|s| s.bytes()).sum()
+    }
+
+    /// Bytes sent inside and outside the local network
+    pub fn lan_wan_bytes(&self) -> (u64, u64) {
+        self.samples.iter().fold((0, 0), |(lan, wan), s| match s.is_lan() {
+            true => (lan + s.bytes(), wan),
+            _ => (lan, wan + s.bytes()),
+        })
+    }
+
+    /// Bytes sent per account id
+    pub fn bytes_per_account(&self) -> HashMap<String, u64> {
+        Self::sum_by(&self.samples, |s| s.account_id.clone())
+    }
+
+    /// Bytes sent per device id
+    pub fn bytes_per_device(&self) -> HashMap<String, u64> {
+        Self::sum_by(&self.samples, |s| s.device_id.clone())
+    }
+
+    /// Bytes sent per sample start, the unix timestamps of the timespan
+    pub fn bytes_over_time(&self) -> Vec<(u64, u64)> {
+        let mut series = Self::sum_by(&self.samples, |s| s.at().unwrap_or(0))
+            .into_iter()
+            .collect::<Vec<_>>();
+        series.sort();
+        series
+    }
+
+    fn sum_by<K, F>(samples: &[BandwidthSample], key: F) -> HashMap<K, u64>
+    where
+        K: ::std::hash::Hash + Eq,
+        F: Fn(&BandwidthSample) -> K,
+    {
+        let mut sums = HashMap::new();
+        for sample in samples {
+            *sums.entry(key(sample)).or_insert(0) += sample.bytes();
+        }
+        sums
+    }
+}
+
+/// Cpu and memory utilization of the host and the server process, in percent
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceSample {
+    pub timespan: String,
+    pub at: String,
+    pub host_cpu_utilization: String,
+    pub process_cpu_utilization: String,
+    pub host_memory_utilization: String,
+    pub process_memory_utilization: String,
+}
+
+impl ResourceSample {
+    /// unix timestamp of the sample
+    pub fn at(&self) -> Option<u64> {
+        self.at.parse().ok()
+    }
+
+    pub fn usage(&self) -> ResourceUsage {
+        let parse = |s: &String| s.parse::<f32>().unwrap_or(0.0);
+        ResourceUsage {
+            host_cpu: parse(&self.host_cpu_utilization),
+            process_cpu: parse(&self.process_cpu_utilization),
+            host_memory: parse(&self.host_memory_utilization),
+            process_memory: parse(&self.process_memory_utilization),
+        }
+    }
+}
+
+/// Utilization in percent
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ResourceUsage {
+    pub host_cpu: f32,
+    pub process_cpu: f32,
+    pub host_memory: f32,
+    pub process_memory: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceStatistics {
+    pub size: String,
+    #[serde(rename = "StatisticsResources", default)]
+    pub samples: Vec<ResourceSample>,
+}
+
+impl ResourceStatistics {
+    pub const PATH: &'static str = "/statistics/resources";
+
+    /// The most recent sample
+    pub fn latest(&self) -> Option<ResourceUsage> {
+        self.samples
+            .iter()
+            .max_by_key(|s| s.at())
+            .map(|s| s.usage())
+    }
+
+    /// Mean utilization over all samples, `None` if there are none
+    pub fn average(&self) -> Option<ResourceUsage> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let n = self.samples.len() as f32;
+        let sum = self.samples
+            .iter()
+            .map(|s| s.usage())
+            .fold(ResourceUsage::default(), |acc, u| ResourceUsage {
+                host_cpu: acc.host_cpu + u.host_cpu,
+                process_cpu: acc.process_cpu + u.process_cpu,
+                host_memory: acc.host_memory + u.host_memory,
+                process_memory: acc.process_memory + u.process_memory,
+            });
+        Some(ResourceUsage {
+            host_cpu: sum.host_cpu / n,
+            process_cpu: sum.process_cpu / n,
+            host_memory: sum.host_memory / n,
+            process_memory: sum.process_memory / n,
+        })
+    }
+
+    /// Highest utilization of each value over all samples, `None` if there are none
+    pub fn peak(&self) -> Option<ResourceUsage> {
+        self.samples.iter().map(|s| s.usage()).fold(None, |acc, u| {
+            Some(match acc {
+                Some(acc) => ResourceUsage {
+                    host_cpu: acc.host_cpu.max(u.host_cpu),
+                    process_cpu: acc.process_cpu.max(u.process_cpu),
+                    host_memory: acc.host_memory.max(u.host_memory),
+                    process_memory: acc.process_memory.max(u.process_memory),
+                },
+                _ => u,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn bandwidth_deserialize() {
+        let xml = fixture!("bandwidth.xml");
+        let stats: Result<BandwidthStatistics, Error> = deserialize(xml.as_bytes());
+        assert!(stats.is_ok());
+        let stats = stats.unwrap();
+        assert_eq!(stats.samples.len(), 3);
+        assert_eq!(stats.total_bytes(), 13820000);
+        assert_eq!(stats.lan_wan_bytes(), (13500000, 320000));
+        assert_eq!(stats.bytes_per_account()["2"], 320000);
+        assert_eq!(stats.bytes_per_device()["3"], 13500000);
+        assert_eq!(
+            stats.bytes_over_time(),
+            vec![(1511736000, 7320000), (1511736006, 6500000)]
+        );
+        assert_eq!(stats.account("2").unwrap().name, "friend");
+        assert_eq!(stats.device("4").unwrap().platform, "iOS");
+    }
+
+    #[test]
+    fn resources_deserialize() {
+        let xml = fixture!("resources.xml");
+        let stats: Result<ResourceStatistics, Error> = deserialize(xml.as_bytes());
+        assert!(stats.is_ok());
+        let stats = stats.unwrap();
+        let average = stats.average().unwrap();
+        assert_eq!(average.host_cpu, 28.5);
+        assert_eq!(average.process_cpu, 20.0);
+        assert!((average.host_memory - 40.6).abs() < 1e-4);
+        let peak = stats.peak().unwrap();
+        assert_eq!(peak.process_memory, 3.3);
+        assert_eq!(stats.latest().unwrap().host_cpu, 35.5);
+        assert!(ResourceStatistics::default().average().is_none());
+    }
+}
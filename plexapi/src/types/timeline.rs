@@ -0,0 +1,157 @@
+use types::session::PlayerState;
+
+/// What a timeline of a player is about
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TimelineType {
+    Video,
+    Music,
+    Photo,
+}
+
+impl TimelineType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TimelineType::Video => "video",
+            TimelineType::Music => "music",
+            TimelineType::Photo => "photo",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<TimelineType> {
+        match s {
+            "video" => Some(TimelineType::Video),
+            "music" => Some(TimelineType::Music),
+            "photo" => Some(TimelineType::Photo),
+            _ => None,
+        }
+    }
+}
+
+/// What a player does with one type of media
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Timeline {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub state: String,
+    /// position in milliseconds
+    pub time: String,
+    /// duration in milliseconds
+    pub duration: String,
+    pub key: String,
+    pub rating_key: String,
+    pub container_key: String,
+    #[serde(rename = "playQueueItemID")]
+    pub play_queue_item_id: String,
+    /// the server the item is played from
+    pub machine_identifier: String,
+    pub address: String,
+    pub port: String,
+    pub protocol: String,
+    pub volume: String,
+    pub shuffle: String,
+    pub repeat: String,
+    /// the commands the player accepts right now, comma separated
+    pub controllable: String,
+}
+
+impl Timeline {
+    pub fn timeline_type(&self) -> Option<TimelineType> {
+        TimelineType::from_str(self.type_.as_str())
+    }
+
+    /// Players leave out the state while nothing is loaded
+    pub fn state(&self) -> PlayerState {
+        PlayerState::from_str(self.state.as_str()).unwrap_or(PlayerState::Stopped)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state() != PlayerState::Stopped
+    }
+
+    pub fn position(&self) -> Option<u64> {
+        self.time.parse().ok()
+    }
+
+    pub fn duration(&self) -> Option<u64> {
+        self.duration.parse().ok()
+    }
+
+    /// The key of the current item, eg. `/library/metadata/444`
+    pub fn key(&self) -> Option<&str> {
+        match self.key.is_empty() {
+            true => None,
+            _ => Some(self.key.as_str()),
+        }
+    }
+
+    pub fn is_controllable(&self, command: &str) -> bool {
+        self.controllable.split(',').any(|c| c.eq(command))
+    }
+}
+
+/// The timelines of a player, one per timeline type
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimelineContainer {
+    #[serde(rename = "commandID")]
+    pub command_id: String,
+    /// eg. `navigation` or `fullScreenVideo`
+    pub location: String,
+    #[serde(rename = "Timeline", default)]
+    pub timelines: Vec<Timeline>,
+}
+
+impl TimelineContainer {
+    pub const POLL: &'static str = "/player/timeline/poll";
+
+    pub fn timeline(&self, timeline_type: TimelineType) -> Option<&Timeline> {
+        self.timelines
+            .iter()
+            .find(|t| t.timeline_type() == Some(timeline_type))
+    }
+
+    pub fn video(&self) -> Option<&Timeline> {
+        self.timeline(TimelineType::Video)
+    }
+
+    pub fn music(&self) -> Option<&Timeline> {
+        self.timeline(TimelineType::Music)
+    }
+
+    pub fn photo(&self) -> Option<&Timeline> {
+        self.timeline(TimelineType::Photo)
+    }
+
+    /// The timeline that is playing, paused or buffering, if any
+    pub fn active(&self) -> Option<&Timeline> {
+        self.timelines.iter().find(|t| t.is_active())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn timeline_deserialize() {
+        let xml = fixture!("timeline.xml");
+        let container: Result<TimelineContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let container = container.unwrap();
+        assert_eq!(container.timelines.len(), 3);
+        assert_eq!(container.location, "fullScreenVideo");
+        assert!(!container.photo().unwrap().is_active());
+        assert_eq!(container.music().unwrap().key(), None);
+
+        let video = container.active().unwrap();
+        assert_eq!(video.timeline_type(), Some(TimelineType::Video));
+        assert_eq!(video.state(), PlayerState::Playing);
+        assert_eq!(video.position(), Some(60000));
+        assert_eq!(video.duration(), Some(2170971));
+        assert_eq!(video.key(), Some("/library/metadata/444"));
+        assert!(video.is_controllable("seekTo"));
+        assert!(!video.is_controllable("skipNext"));
+    }
+}
@@ -0,0 +1,164 @@
+/// What the transcoder does with a stream
+#[derive(Debug, PartialEq, Clone)]
+pub enum StreamDecision {
+    DirectPlay,
+    Copy,
+    Transcode,
+    /// subtitles that are burned into the video
+    Burn,
+}
+
+impl StreamDecision {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            StreamDecision::DirectPlay => "directplay",
+            StreamDecision::Copy => "copy",
+            StreamDecision::Transcode => "transcode",
+            StreamDecision::Burn => "burn",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<StreamDecision> {
+        match s {
+            "directplay" => Some(StreamDecision::DirectPlay),
+            "copy" => Some(StreamDecision::Copy),
+            "transcode" => Some(StreamDecision::Transcode),
+            "burn" => Some(StreamDecision::Burn),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranscodeSession {
+    pub key: String,
+    pub throttled: String,
+    pub complete: String,
+    pub progress: String,
+    pub speed: String,
+    pub size: String,
+    pub duration: String,
+    pub remaining: String,
+    pub context: String,
+    pub source_video_codec: String,
+    pub source_audio_codec: String,
+    pub video_decision: String,
+    pub audio_decision: String,
+    pub subtitle_decision: String,
+    pub protocol: String,
+    pub container: String,
+    pub video_codec: String,
+    pub audio_codec: String,
+    pub audio_channels: String,
+    pub transcode_hw_requested: String,
+    pub transcode_hw_decoding: String,
+    pub transcode_hw_encoding: String,
+    pub transcode_hw_full_pipeline: String,
+    pub time_stamp: String,
+    pub max_offset_available: String,
+    pub min_offset_available: String,
+}
+
+impl TranscodeSession {
+    /// The id of the session, the key is either the bare id or the path of the session
+    pub fn session_id(&self) -> &str {
+        self.key.rsplit('/').next().unwrap_or("")
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        "1".eq(&self.throttled)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        "1".eq(&self.complete)
+    }
+
+    /// progress in percent
+    pub fn progress(&self) -> Option<f32> {
+        self.progress.parse().ok()
+    }
+
+    /// transcoding speed relative to realtime playback
+    pub fn speed(&self) -> Option<f32> {
+        self.speed.parse().ok()
+    }
+
+    pub fn video_decision(&self) -> Option<StreamDecision> {
+        StreamDecision::from_str(self.video_decision.as_str())
+    }
+
+    pub fn audio_decision(&self) -> Option<StreamDecision> {
+        StreamDecision::from_str(self.audio_decision.as_str())
+    }
+
+    pub fn subtitle_decision(&self) -> Option<StreamDecision> {
+        StreamDecision::from_str(self.subtitle_decision.as_str())
+    }
+
+    pub fn is_hw_requested(&self) -> bool {
+        "1".eq(&self.transcode_hw_requested)
+    }
+
+    pub fn is_hw_decoding(&self) -> bool {
+        !self.transcode_hw_decoding.is_empty()
+    }
+
+    pub fn is_hw_encoding(&self) -> bool {
+        !self.transcode_hw_encoding.is_empty()
+    }
+
+    /// decoding and encoding both happen on the gpu
+    pub fn is_hw_full_pipeline(&self) -> bool {
+        "1".eq(&self.transcode_hw_full_pipeline)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranscodeSessionContainer {
+    pub size: String,
+    #[serde(rename = "TranscodeSession", default)]
+    pub sessions: Vec<TranscodeSession>,
+}
+
+impl TranscodeSessionContainer {
+    pub const PATH: &'static str = "/transcode/sessions";
+    pub const STOP: &'static str = "/video/:/transcode/universal/stop";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn transcode_sessions_deserialize() {
+        let xml = fixture!("transcode_sessions.xml");
+        let container: Result<TranscodeSessionContainer, Error> = deserialize(xml.as_bytes());
+        assert!(container.is_ok());
+        let sessions = container.unwrap().sessions;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id(), "5pj5r8ec3j1m8ad27fsn5vdb");
+        assert_eq!(sessions[0].progress(), Some(45.7));
+        assert_eq!(sessions[0].video_decision(), Some(StreamDecision::Transcode));
+        assert_eq!(sessions[0].subtitle_decision(), None);
+        assert!(sessions[0].is_hw_decoding() && sessions[0].is_hw_full_pipeline());
+    }
+
+    #[test]
+    fn finished_transcode_session() {
+        let session = TranscodeSession {
+            key: "/transcode/sessions/ab12cd34".to_string(),
+            throttled: "1".to_string(),
+            complete: "1".to_string(),
+            subtitle_decision: "burn".to_string(),
+            transcode_hw_requested: "0".to_string(),
+            ..TranscodeSession::default()
+        };
+        assert_eq!(session.session_id(), "ab12cd34");
+        assert_eq!(session.subtitle_decision(), Some(StreamDecision::Burn));
+        assert!(session.is_throttled() && session.is_complete());
+        assert!(!session.is_hw_requested() && !session.is_hw_encoding());
+    }
+}
@@ -0,0 +1,134 @@
+use errors::APIError;
+use types::server::ServerVersion;
+
+/// How far the server got with a release
+#[derive(Debug, PartialEq, Clone)]
+pub enum ReleaseState {
+    /// the release is available but nothing happened yet
+    Notify,
+    Downloading,
+    Downloaded,
+    Installing,
+    Unknown(String),
+}
+
+impl ReleaseState {
+    pub fn from_str(s: &str) -> ReleaseState {
+        match s {
+            "notify" => ReleaseState::Notify,
+            "downloading" => ReleaseState::Downloading,
+            "downloaded" => ReleaseState::Downloaded,
+            "installing" => ReleaseState::Installing,
+            _ => ReleaseState::Unknown(s.to_string()),
+        }
+    }
+}
+
+/// A server release the updater found
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Release {
+    pub key: String,
+    pub version: String,
+    /// new features, one per line
+    pub added: String,
+    /// fixed bugs, one per line
+    pub fixed: String,
+    #[serde(rename = "downloadURL")]
+    pub download_url: String,
+    pub state: String,
+}
+
+impl Release {
+    pub fn version(&self) -> Result<ServerVersion, APIError> {
+        self.version.parse()
+    }
+
+    pub fn state(&self) -> ReleaseState {
+        ReleaseState::from_str(self.state.as_str())
+    }
+
+    /// The release notes, new features first
+    pub fn changelog(&self) -> String {
+        let mut changelog = String::new();
+        if !self.added.is_empty() {
+            changelog.push_str("NEW:\n");
+            changelog.push_str(self.added.as_str());
+        }
+        if !self.fixed.is_empty() {
+            if !changelog.is_empty() {
+                changelog.push_str("\n\n");
+            }
+            changelog.push_str("FIXES:\n");
+            changelog.push_str(self.fixed.as_str());
+        }
+        changelog
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdaterStatus {
+    pub size: String,
+    pub can_install: String,
+    pub checked_at: String,
+    #[serde(rename = "downloadURL")]
+    pub download_url: String,
+    pub status: String,
+    #[serde(rename = "Release", default)]
+    pub releases: Vec<Release>,
+}
+
+impl UpdaterStatus {
+    pub const PATH: &'static str = "/updater/status";
+    pub const CHECK: &'static str = "/updater/check";
+    pub const APPLY: &'static str = "/updater/apply";
+
+    /// Whether the server is able to install updates by itself,
+    /// eg. it is not when it was installed through a package manager
+    pub fn can_install(&self) -> bool {
+        "1".eq(&self.can_install)
+    }
+
+    /// unix timestamp of the last check for updates
+    pub fn checked_at(&self) -> Option<u64> {
+        self.checked_at.parse().ok()
+    }
+
+    /// The most recent release that is newer than `current`
+    pub fn available_release(&self, current: &ServerVersion) -> Option<&Release> {
+        self.releases
+            .iter()
+            .filter_map(|r| r.version().ok().map(|v| (v, r)))
+            .filter(|&(ref v, _)| v > current)
+            .max_by(|&(ref a, _), &(ref b, _)| a.cmp(b))
+            .map(|(_, r)| r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_xml_rs::{deserialize, Error};
+
+    #[test]
+    fn updater_status_deserialize() {
+        let xml = fixture!("updater.xml");
+        let status: Result<UpdaterStatus, Error> = deserialize(xml.as_bytes());
+        assert!(status.is_ok());
+        let status = status.unwrap();
+        assert!(status.can_install());
+        assert_eq!(status.checked_at(), Some(1545150000));
+
+        let current = ServerVersion::new(1, 13, 5, 5291);
+        let release = status.available_release(&current).unwrap();
+        assert_eq!(release.state(), ReleaseState::Notify);
+        assert_eq!(
+            release.changelog(),
+            "NEW:\nSupport for the new agents\n\nFIXES:\nSessions could not be terminated"
+        );
+        assert!(status
+            .available_release(&ServerVersion::new(1, 14, 1, 5488))
+            .is_none());
+    }
+}
@@ -1,9 +1,825 @@
+extern crate futures;
+extern crate hyper;
+extern crate hyper_tls;
 extern crate plexapi;
+extern crate tokio_core;
+extern crate websocket;
+
+pub mod mock;
+
+use hyper::{Body, Client};
+use hyper::client::HttpConnector;
+use hyper_tls::HttpsConnector;
+use tokio_core::reactor::Core;
+
+/// The client all plexapi requests are sent with
+pub fn https_client(core: &Core) -> Client<HttpsConnector<HttpConnector>, Body> {
+    let handle = core.handle();
+    Client::configure()
+        .connector(HttpsConnector::new(1, &handle).unwrap())
+        .build(&handle)
+}
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use futures::{Future, Stream};
+    use hyper::Method;
+    use mock::{fixture, MockGdmResponder, MockNotifications, MockServer, Routes, DEVICES, SIGN_IN};
+    use plexapi::client::{Plex, PlexClient};
+    use plexapi::client::fixture::{Transport, SCRUBBED};
+    use plexapi::client::gdm::Discovery;
+    use plexapi::errors::APIError;
+    use plexapi::types::account::Login;
+    use plexapi::types::browse::BrowseNode;
+    use plexapi::types::butler::ButlerTaskName;
+    use plexapi::types::companion::StreamType;
+    use plexapi::types::device::{Connection, PlexDeviceType};
+    use plexapi::types::sections::{LibrarySection, MusicSection};
+    use plexapi::types::feature::Feature;
+    use plexapi::types::filter::MovieLibraryFilter;
+    use plexapi::types::history::{HistoryContainer, HistoryFilter};
+    use plexapi::types::media::photo::PhotoItem;
+    use plexapi::types::hub::{HubItem, HubType};
+    use plexapi::types::notification::{Notification, NotificationStream};
+    use plexapi::types::server::{Identity, MappingState, PlexServer, ServerVersion};
+    use plexapi::types::session::PlayerState;
+    use plexapi::types::statistics::StatisticsTimespan;
+    use std::{env, fs, process};
+    use std::io::Read;
+    use std::time::Duration;
+
+    /// A `Plex` that looks up its devices on the mock, the http client is leaked so that
+    /// everything created through it can outlive the helper
+    fn mock_plex(core: &Core, mock: &MockServer) -> Plex<'static> {
+        let client = Box::leak(Box::new(https_client(core)));
+        Plex::with_devices_url(client, "mocktoken".to_string(), mock.url(DEVICES).as_str())
+    }
+
+    /// A server that is connected to the mock directly
+    fn connect_mock(mock: &MockServer) -> (Core, PlexServer<'static>) {
+        let mut core = Core::new().unwrap();
+        let plex = mock_plex(&core, mock);
+        let server = core.run(plex.connect(mock.addr().to_string().as_str()))
+            .unwrap();
+        (core, server)
+    }
+
     #[test]
     fn it_works() {
         assert!(true)
     }
+
+    #[test]
+    fn mock_sign_in() {
+        let mock = MockServer::start();
+        let mut core = Core::new().unwrap();
+        let client = https_client(&core);
+        let token = core.run(Login::new("mock", "secret").sign_in(&client, mock.url(SIGN_IN).as_str()));
+        assert_eq!(token.unwrap(), "mocktoken");
+    }
+
+    #[test]
+    fn mock_device_chain() {
+        let mock = MockServer::start();
+        let mut core = Core::new().unwrap();
+        let plex = mock_plex(&core, &mock);
+
+        let devices = core.run(plex.devices()).unwrap();
+        assert_eq!(devices.len(), 2);
+
+        let device = core.run(plex.select_device("Mock")).unwrap();
+        let server = core.run(device.connect()).unwrap();
+        assert_eq!(server.inner.directories.len(), 2);
+
+        let library = core.run(server.library()).unwrap();
+        let sections = core.run(library.sections()).unwrap();
+        assert_eq!(sections.len(), 2);
+
+        let movie_sections = core.run(library.movie_sections()).unwrap();
+        assert_eq!(movie_sections.len(), 1);
+        let movies = core.run(movie_sections[0].all()).unwrap();
+        assert_eq!(movies.len(), 2);
+        assert_eq!(movies[0].title, "First Movie");
+
+        let filter = vec![MovieLibraryFilter::Genre(vec!["Action".to_string()])];
+        let found = core.run(movie_sections[0].search(Some("Movie"), filter, Some(1)))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            core.run(movie_sections[0].search(None, Vec::new(), None))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let music = core.run(library.section("Music"))
+            .unwrap()
+            .into::<MusicSection>()
+            .unwrap();
+        let albums = core.run(music.albums()).unwrap();
+        assert_eq!(albums.len(), 1);
+        let tracks = core.run(albums[0].tracks()).unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[1].inner.title, "Second Track");
+    }
+
+    #[test]
+    fn mock_search_pages() {
+        // the server hands out one movie per page, the total tells that more follow
+        let search = "/library/sections/1/all?type=1&title=Movie";
+        let mock = MockServer::with_routes(
+            Routes::plex()
+                .page(search, 0, "movies_first_page.xml")
+                .page(search, 1, "movies_second_page.xml"),
+        );
+        let (mut core, server) = connect_mock(&mock);
+        let library = core.run(server.library()).unwrap();
+        let movies = core.run(library.movie_sections()).unwrap();
+
+        let filter = vec![
+            MovieLibraryFilter::Genre(vec!["Action".to_string(), "Sci Fi".to_string()]),
+        ];
+        let found = core.run(movies[0].search(Some("Movie"), filter, None))
+            .unwrap();
+        let titles = found.iter().map(|v| v.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(titles, vec!["First Movie", "Second Movie"]);
+
+        let requests = mock.requests_to("/library/sections/1/all");
+        let starts = requests.iter().map(|r| r.container_start).collect::<Vec<_>>();
+        assert_eq!(starts, vec![Some(0), Some(1)]);
+        for request in requests {
+            assert_eq!(request.param("type"), Some("1"));
+            assert_eq!(request.param("title"), Some("Movie"));
+            assert_eq!(request.param("genre"), Some("Action,Sci+Fi"));
+        }
+
+        // a server that ignores the start keeps answering with the first page
+        let mock = MockServer::with_routes(Routes::plex().fixture(search, "movies_first_page.xml"));
+        let (mut core, server) = connect_mock(&mock);
+        let library = core.run(server.library()).unwrap();
+        let movies = core.run(library.movie_sections()).unwrap();
+        let found = core.run(movies[0].search(Some("Movie"), Vec::new(), None))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(mock.requests_to("/library/sections/1/all").len(), 2);
+    }
+
+    #[test]
+    fn mock_connect_direct() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+        let library = core.run(server.library()).unwrap();
+        assert_eq!(core.run(library.sections()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn mock_identity_and_capabilities() {
+        let mock = MockServer::start();
+        let mut core = Core::new().unwrap();
+        let client = https_client(&core);
+        let conn = Connection::from_endoint(mock.addr());
+
+        let identity = core.run(Identity::probe(&client, &conn)).unwrap();
+        assert!(identity.is_claimed());
+        assert_eq!(identity.machine_identifier, "mockserveridentifier");
+
+        let plex = Plex::new(&client, "mocktoken".to_string());
+        let server = core.run(plex.connect(mock.addr().to_string().as_str()))
+            .unwrap();
+        let capabilities = server.capabilities().unwrap();
+        assert_eq!(capabilities.version, identity.version().unwrap());
+        assert!(capabilities.version >= ServerVersion::new(1, 10, 0, 0));
+        assert_eq!(capabilities.mapping_state, MappingState::Mapped);
+        assert!(capabilities.plex_pass);
+    }
+
+    #[test]
+    fn mock_unsupported_version() {
+        let server_xml = fixture("server.xml").replace("1.10.1.4602-f54242b6b", "0.8.0.0");
+        let mock = MockServer::with_routes(Routes::plex().body("/", server_xml.as_str()));
+        let (mut core, server) = connect_mock(&mock);
+        assert!(!server.supports(Feature::Library));
+        match core.run(server.library()) {
+            Err(APIError::UnsupportedVersion { feature, found, .. }) => {
+                assert_eq!(feature, Feature::Library);
+                assert_eq!(found, ServerVersion::new(0, 8, 0, 0));
+            }
+            other => panic!("expected unsupported version, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn mock_preferences() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let settings = core.run(server.preferences()).unwrap();
+        assert_eq!(settings.len(), 3);
+        assert_eq!(settings[2].group, "transcoder");
+
+        core.run(server.set_preference("TranscoderQuality", 2i64))
+            .unwrap();
+        match core.run(server.set_preference("TranscoderQuality", 9i64)) {
+            Err(APIError::InvalidSetting(_)) => (),
+            other => panic!("expected invalid setting, got {:?}", other),
+        }
+        // the invalid value was never sent
+        let updates = mock.requests_to("/:/prefs")
+            .into_iter()
+            .filter(|r| r.method == Method::Put)
+            .collect::<Vec<_>>();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].param("TranscoderQuality"), Some("2"));
+    }
+
+    #[test]
+    fn mock_sessions() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let sessions = core.run(server.sessions()).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].player.address, "192.168.1.20");
+        assert!(sessions[0].is_transcoding());
+        assert_eq!(sessions[1].user.title, "friend");
+
+        core.run(server.terminate(&sessions[0], "Family movie night"))
+            .unwrap();
+        let terminated = mock.requests_to("/status/sessions/terminate");
+        assert_eq!(terminated.len(), 1);
+        assert_eq!(terminated[0].param("sessionId"), Some("abcdef"));
+        assert_eq!(terminated[0].param("reason"), Some("Family+movie+night"));
+
+        let mut without_id = sessions[1].clone();
+        without_id.session = None;
+        match core.run(server.terminate(&without_id, "")) {
+            Err(APIError::MissingSessionId(key)) => assert_eq!(key, "13"),
+            other => panic!("expected a missing session id, got {:?}", other),
+        }
+        assert_eq!(mock.requests_to("/status/sessions/terminate").len(), 1);
+    }
+
+    #[test]
+    fn mock_history() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let history = core.run(server.history(vec![], None)).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].title, "First Track");
+        assert_eq!(history[1].viewed_at(), Some(1511736000));
+
+        let filters = vec![
+            HistoryFilter::Account("1".to_string()),
+            HistoryFilter::ViewedAfter(1511000000),
+        ];
+        let history = core.run(server.history(filters, Some(2))).unwrap();
+        assert_eq!(history.len(), 2);
+        let request = mock.requests_to(HistoryContainer::PATH).pop().unwrap();
+        assert_eq!(request.param("accountID"), Some("1"));
+        assert_eq!(request.param("viewedAt%3E"), Some("1511000000"));
+        assert_eq!(request.param("sort"), Some("viewedAt%3Adesc"));
+        assert_eq!(request.container_size, Some(2));
+    }
+
+    #[test]
+    fn mock_history_pages() {
+        // the server hands out fewer entries than asked for, the total tells that more follow
+        let mock = MockServer::with_routes(
+            Routes::plex()
+                .page(HistoryContainer::PATH, 0, "history_first_page.xml")
+                .page(HistoryContainer::PATH, 2, "history_second_page.xml"),
+        );
+        let (mut core, server) = connect_mock(&mock);
+
+        let filters = vec![HistoryFilter::LibrarySection("1".to_string())];
+        let history = core.run(server.history(filters, None)).unwrap();
+        let titles = history.iter().map(|e| e.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(titles, vec!["First Movie", "Second Movie", "Third Movie"]);
+
+        let requests = mock.requests_to(HistoryContainer::PATH);
+        let starts = requests.iter().map(|r| r.container_start).collect::<Vec<_>>();
+        assert_eq!(starts, vec![Some(0), Some(2)]);
+        assert!(requests.iter().all(|r| r.param("librarySectionID") == Some("1")));
+    }
+
+    #[test]
+    fn mock_activities_and_butler() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let activities = core.run(server.activities()).unwrap();
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].progress(), Some(42));
+        core.run(server.cancel_activity(activities[0].uuid.as_str()))
+            .unwrap();
+        let cancelled = mock.requests_to("/activities/1bd4a9a4-8e5b-4c3c-a6b4-8d1a0b9a3f10");
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].method, Method::Delete);
+        match core.run(server.cancel_activity("unknown")) {
+            Err(APIError::HttpError(status)) => assert_eq!(status.as_u16(), 404),
+            other => panic!("expected not found, got {:?}", other),
+        }
+
+        let tasks = core.run(server.butler_tasks()).unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].task(), ButlerTaskName::BackupDatabase);
+        core.run(server.start_butler_task(&ButlerTaskName::BackupDatabase))
+            .unwrap();
+        core.run(server.stop_butler_task(&ButlerTaskName::BackupDatabase))
+            .unwrap();
+        let methods = mock.requests_to("/butler/BackupDatabase")
+            .into_iter()
+            .map(|r| r.method)
+            .collect::<Vec<_>>();
+        assert_eq!(methods, vec![Method::Post, Method::Delete]);
+    }
+
+    #[test]
+    fn mock_statistics() {
+        // the fixture server predates the statistics endpoints
+        let server_xml = fixture("server.xml").replace("1.10.1.4602-f54242b6b", "1.12.0.4829-6de959918");
+        let mock = MockServer::with_routes(Routes::plex().body("/", server_xml.as_str()));
+        let (mut core, server) = connect_mock(&mock);
+
+        let bandwidth = core.run(server.bandwidth_statistics(StatisticsTimespan::Seconds))
+            .unwrap();
+        assert_eq!(bandwidth.total_bytes(), 13820000);
+        assert_eq!(bandwidth.lan_wan_bytes(), (13500000, 320000));
+        assert_eq!(bandwidth.bytes_per_account()["1"], 13500000);
+
+        let resources = core.run(server.resource_statistics(StatisticsTimespan::Seconds))
+            .unwrap();
+        assert_eq!(resources.samples.len(), 2);
+        assert_eq!(resources.latest().unwrap().process_cpu, 28.0);
+        assert_eq!(resources.peak().unwrap().host_memory, 41.0);
+    }
+
+    #[test]
+    fn mock_transcode_sessions() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let transcodes = core.run(server.transcode_sessions()).unwrap();
+        assert_eq!(transcodes.len(), 1);
+        assert_eq!(transcodes[0].speed(), Some(2.5));
+        assert!(transcodes[0].is_hw_full_pipeline());
+        core.run(server.stop_transcode(&transcodes[0])).unwrap();
+
+        // the transcoder of a playback session can be stopped as well
+        let sessions = core.run(server.sessions()).unwrap();
+        let transcode = sessions[0].transcode_session.as_ref().unwrap();
+        assert_eq!(transcode.session_id(), "xyz");
+        core.run(server.stop_transcode(transcode)).unwrap();
+
+        let stopped = mock.requests_to("/video/:/transcode/universal/stop")
+            .iter()
+            .map(|r| r.param("session").unwrap_or("").to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(stopped, vec!["5pj5r8ec3j1m8ad27fsn5vdb", "xyz"]);
+    }
+
+    #[test]
+    fn mock_updater() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let status = core.run(server.update_status()).unwrap();
+        assert!(status.can_install());
+        let release = core.run(server.available_update()).unwrap().unwrap();
+        assert_eq!(release.version().unwrap(), ServerVersion::new(1, 14, 1, 5488));
+        assert!(release.changelog().contains("Sessions could not be terminated"));
+        core.run(server.install_update()).unwrap();
+        let applied = mock.requests_to("/updater/apply");
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].method, Method::Put);
+    }
+
+    #[test]
+    fn mock_player_control() {
+        let mock = MockServer::start();
+        let mut core = Core::new().unwrap();
+        let plex = mock_plex(&core, &mock);
+
+        let players = core.run(plex.select_device_type(PlexDeviceType::PlexMediaPlayer))
+            .unwrap();
+        assert_eq!(players.len(), 1);
+        let player = players[0].player().unwrap();
+        assert_eq!(player.machine_identifier(), "mockplayeridentifier");
+
+        let server = core.run(plex.select_device("Mock").and_then(|d| d.connect()))
+            .unwrap();
+        core.run(player.play_media(&server, "/library/metadata/444", 0))
+            .unwrap();
+        core.run(player.pause()).unwrap();
+        core.run(player.seek_to(60000)).unwrap();
+        core.run(player.set_volume(150)).unwrap();
+        core.run(player.select_stream(StreamType::Subtitle, "3"))
+            .unwrap();
+        core.run(player.play()).unwrap();
+        // commands the player does not know fail
+        match core.run(player.skip_next()) {
+            Err(APIError::HttpError(status)) => assert_eq!(status.as_u16(), 404),
+            other => panic!("expected not found, got {:?}", other),
+        }
+
+        let commands = mock.requests()
+            .into_iter()
+            .filter(|r| r.path.starts_with("/player/playback/"))
+            .collect::<Vec<_>>();
+        let paths = commands.iter().map(|r| r.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "/player/playback/playMedia",
+                "/player/playback/pause",
+                "/player/playback/seekTo",
+                "/player/playback/setParameters",
+                "/player/playback/setStreams",
+                "/player/playback/play",
+                "/player/playback/skipNext",
+            ]
+        );
+        assert_eq!(commands[0].param("key"), Some("%2Flibrary%2Fmetadata%2F444"));
+        assert_eq!(commands[0].param("machineIdentifier"), Some("mockserveridentifier"));
+        assert_eq!(commands[2].param("offset"), Some("60000"));
+        assert_eq!(commands[3].param("volume"), Some("100"));
+        assert_eq!(commands[4].param("subtitleStreamID"), Some("3"));
+        // every command gets its own id
+        let ids = commands
+            .iter()
+            .map(|r| r.param("commandID").unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["1", "2", "3", "4", "5", "6", "7"]);
+    }
+
+    #[test]
+    fn mock_player_timelines() {
+        let mock = MockServer::start();
+        let mut core = Core::new().unwrap();
+        let plex = mock_plex(&core, &mock);
+
+        let players = core.run(plex.select_device_type(PlexDeviceType::PlexMediaPlayer))
+            .unwrap();
+        let player = players[0].player().unwrap();
+        let timelines = core.run(player.timeline()).unwrap();
+        assert_eq!(timelines.timelines.len(), 3);
+        let video = timelines.active().unwrap();
+        assert_eq!(video.state(), PlayerState::Playing);
+        assert_eq!(video.key(), Some("/library/metadata/444"));
+        assert_eq!(video.position(), Some(60000));
+        assert_eq!(video.duration(), Some(2170971));
+
+        // the mock answers waiting polls right away
+        let updates = core.run(player.timelines(&core.handle()).take(3).collect())
+            .unwrap();
+        assert_eq!(updates.len(), 3);
+        assert!(updates.iter().all(|t| t.video().unwrap().is_active()));
+    }
+
+    #[test]
+    fn mock_server_clients() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let clients = core.run(server.clients()).unwrap();
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].name, "Mock Player");
+        assert!(clients[0].is_controllable());
+
+        assert_eq!(
+            clients[0].connection().endpoint(),
+            format!("http://{}", mock.addr())
+        );
+
+        // commands are passed on by the server
+        let player = server.player(&clients[0]);
+        assert_eq!(player.machine_identifier(), "mockplayeridentifier");
+        assert_eq!(player.connection(), server.connection());
+        core.run(player.pause()).unwrap();
+        let paused = mock.requests_to("/player/playback/pause");
+        assert_eq!(paused.len(), 1);
+        assert_eq!(paused[0].param("commandID"), Some("1"));
+    }
+
+    #[test]
+    fn mock_hub_search() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let hubs = core.run(server.search("first", 5, Some("1"))).unwrap();
+        let types = hubs.iter().map(|h| h.hub_type()).collect::<Vec<_>>();
+        assert_eq!(types, vec![HubType::Movie, HubType::Track, HubType::Actor]);
+        match hubs[0].items()[0] {
+            HubItem::Movie(ref movie) => assert_eq!(movie.rating_key, "444"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+        match hubs[1].items()[0] {
+            HubItem::Track(ref track) => assert_eq!(track.grandparent_title, "Artist"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mock_hubs() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let hubs = core.run(server.hubs()).unwrap();
+        let titles = hubs.iter().map(|h| h.title.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec!["Continue Watching", "Recently Added Movies", "Recently Played Artists"]
+        );
+        match hubs[2].items()[0] {
+            HubItem::Artist(ref artist) => assert_eq!(artist.title, "Artist"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+        assert!(hubs[1].has_more());
+        let recent = core.run(server.hub_items(hubs[1].clone())).unwrap();
+        assert!(!recent.has_more());
+        assert_eq!(recent.hub_identifier, "home.movies.recent");
+        assert_eq!(recent.items().len(), 2);
+
+        let library = core.run(server.library()).unwrap();
+        let movie_sections = core.run(library.movie_sections()).unwrap();
+        let section_hubs = core.run(movie_sections[0].hubs()).unwrap();
+        assert_eq!(section_hubs.len(), 2);
+        assert_eq!(section_hubs[0].style, "hero");
+        assert!(section_hubs[1].is_empty());
+    }
+
+    #[test]
+    fn mock_browse() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+
+        let root = server.root_directories()[0].clone();
+        assert_eq!(root.title, "library");
+        let library = core.run(server.browse_directory("/", &root)).unwrap();
+        let sections = library.directories()[0].clone();
+        assert_eq!(sections.key, "sections");
+
+        let node = core.run(server.browse_directory("/library", &sections))
+            .unwrap();
+        assert_eq!(node.directories().len(), 2);
+        let movies = node.directories()[0].path("/library/sections");
+        assert_eq!(movies, "/library/sections/1");
+        let node = core.run(server.browse(format!("{}/all", movies).as_str()))
+            .unwrap();
+        match node {
+            BrowseNode::Items(ref container) => assert_eq!(container.videos.len(), 2),
+            ref other => panic!("unexpected node {:?}", other),
+        }
+
+        match core.run(server.browse("/identity")).unwrap() {
+            BrowseNode::Raw(ref body) => assert!(body.contains("mockserveridentifier")),
+            ref other => panic!("unexpected node {:?}", other),
+        }
+        match core.run(server.browse("/nothing/here")) {
+            Err(APIError::HttpError(status)) => assert_eq!(status.as_u16(), 404),
+            other => panic!("expected not found, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mock_show_section() {
+        let mock = MockServer::with_routes(
+            Routes::plex().fixture("/library/sections", "show_sections.xml"),
+        );
+        let (mut core, server) = connect_mock(&mock);
+        let library = core.run(server.library()).unwrap();
+
+        let show_sections = core.run(library.show_sections()).unwrap();
+        assert_eq!(show_sections.len(), 1);
+        let shows = core.run(show_sections[0].shows()).unwrap();
+        assert_eq!(shows.len(), 1);
+        assert_eq!(shows[0].inner.title, "First Show");
+
+        let seasons = core.run(shows[0].seasons()).unwrap();
+        assert_eq!(seasons.len(), 2);
+        let episodes = core.run(seasons[0].episodes()).unwrap();
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[1].inner.title, "Second Episode");
+        assert_eq!(core.run(shows[0].episodes()).unwrap().len(), 2);
+
+        // and back up again
+        let season = core.run(episodes[0].season()).unwrap();
+        assert_eq!(season.inner.title, "Season 1");
+        let show = core.run(episodes[0].show()).unwrap();
+        assert_eq!(show.inner.rating_key, "100");
+        assert_eq!(core.run(season.show()).unwrap().inner.title, "First Show");
+    }
+
+    #[test]
+    fn mock_artist_browsing() {
+        let mock = MockServer::start();
+        let (mut core, server) = connect_mock(&mock);
+        let library = core.run(server.library()).unwrap();
+
+        let music = core.run(library.section("Music"))
+            .unwrap()
+            .into::<MusicSection>()
+            .unwrap();
+        let artists = core.run(music.artists()).unwrap();
+        assert_eq!(artists.len(), 1);
+        assert_eq!(artists[0].inner.title, "Artist");
+        let section_tracks = core.run(music.tracks()).unwrap();
+        assert_eq!(section_tracks.len(), 2);
+        let requests = mock.requests_to("/library/sections/2/all");
+        let types = requests.iter().map(|r| r.param("type")).collect::<Vec<_>>();
+        assert_eq!(types, vec![Some("8"), Some("10")]);
+        assert!(requests.iter().all(|r| r.container_start == Some(0)));
+
+        let albums = core.run(artists[0].albums()).unwrap();
+        assert_eq!(albums.len(), 1);
+        assert_eq!(core.run(artists[0].tracks()).unwrap().len(), 2);
+        let top = core.run(artists[0].top_tracks(1)).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].inner.title, "Second Track");
+        let similar = core.run(artists[0].similar()).unwrap();
+        assert_eq!(similar.len(), 2);
+        assert_eq!(similar[0].tag, "Other Artist");
+
+        // and back up again
+        let tracks = core.run(albums[0].tracks()).unwrap();
+        let album = core.run(tracks[0].album()).unwrap();
+        assert_eq!(album.inner.title, "Album");
+        assert_eq!(core.run(tracks[0].artist()).unwrap().inner.rating_key, "13");
+        assert_eq!(core.run(album.artist()).unwrap().inner.title, "Artist");
+    }
+
+    #[test]
+    fn mock_photo_section() {
+        let mock = MockServer::with_routes(
+            Routes::plex().fixture("/library/sections", "photo_sections.xml"),
+        );
+        let (mut core, server) = connect_mock(&mock);
+        let library = core.run(server.library()).unwrap();
+
+        let photo_sections = core.run(library.photo_sections()).unwrap();
+        assert_eq!(photo_sections.len(), 1);
+        let section = &photo_sections[0];
+        let items = core.run(section.items()).unwrap();
+        assert_eq!(items.len(), 3);
+        let album = match items[0] {
+            PhotoItem::Album(ref album) => album.clone(),
+            ref other => panic!("unexpected item {:?}", other),
+        };
+        match items[2] {
+            PhotoItem::Clip(ref clip) => assert_eq!(clip.title, "MOV_0002"),
+            ref other => panic!("unexpected item {:?}", other),
+        }
+
+        let photos = core.run(album.photos()).unwrap();
+        assert_eq!(photos.len(), 2);
+        assert_eq!(photos[1].camera(), Some("Canon EOS 5D".to_string()));
+        assert_eq!(photos[1].dimensions(), Some((5616, 3744)));
+
+        assert_eq!(core.run(section.years()).unwrap(), vec![2017, 2016]);
+        let taken = core.run(section.photos_taken_in(2017, Some(7))).unwrap();
+        assert_eq!(taken[0].captured_at(), Some("2017-07-16"));
+        let query = mock.requests_to("/library/sections/4/all").pop().unwrap();
+        assert_eq!(query.param("originallyAvailableAt%3E%3E"), Some("2017-07-01"));
+        assert_eq!(query.param("originallyAvailableAt%3C%3C"), Some("2017-08-01"));
+        for month in &[0, 13] {
+            match core.run(section.photos_taken_in(2017, Some(*month))) {
+                Err(APIError::InvalidArgument(_)) => (),
+                other => panic!("expected invalid argument, got {:?}", other),
+            }
+        }
+        match core.run(section.photos_taken_in(u16::max_value(), None)) {
+            Err(APIError::InvalidArgument(_)) => (),
+            other => panic!("expected invalid argument, got {:?}", other),
+        }
+        // invalid arguments are rejected before anything is sent
+        assert_eq!(mock.requests_to("/library/sections/4/all").len(), 2);
+    }
+
+    #[test]
+    fn gdm_discovery() {
+        let mock = MockServer::start();
+        let responder = MockGdmResponder::start("Mock", mock.addr().port());
+        let mut core = Core::new().unwrap();
+
+        let mut discovery = Discovery::with_destination(responder.addr());
+        discovery.set_timeout(Duration::from_millis(300));
+        let responses = core.run(discovery.discover(&core.handle())).unwrap();
+        assert_eq!(responses.len(), 1);
+        assert!(responses[0].is_server());
+        assert_eq!(responses[0].name(), Some("Mock"));
+
+        // the discovered address leads to the server without plex.tv
+        let addr = responses[0].address().unwrap();
+        assert_eq!(addr, mock.addr());
+        let client = https_client(&core);
+        let plex = Plex::new(&client, "mocktoken".to_string());
+        let server = core.run(plex.connect(addr.to_string().as_str())).unwrap();
+        assert_eq!(server.inner.machine_identifier(), "mockserveridentifier");
+    }
+
+    #[test]
+    fn mock_gdm_players() {
+        let responder = MockGdmResponder::player("Living Room", 32500);
+        let mut core = Core::new().unwrap();
+
+        let mut discovery = Discovery::with_destination(responder.addr());
+        discovery.set_timeout(Duration::from_millis(300));
+        let responses = core.run(discovery.discover(&core.handle())).unwrap();
+        // both answers come from the same address, so they are one player
+        assert_eq!(responses.len(), 1);
+        assert!(responses[0].is_player());
+        assert_eq!(responses[0].resource_identifier(), None);
+        assert_eq!(responses[0].name(), Some("Living Room"));
+        assert_eq!(
+            responses[0].address(),
+            Some("127.0.0.1:32500".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn mock_notifications_reconnect() {
+        let playing = r#"{"NotificationContainer":{"type":"playing","size":1,
+            "PlaySessionStateNotification":[{"sessionKey":"12","state":"playing","viewOffset":1000}]}}"#;
+        let malformed = r#"{"NotificationContainer":"#;
+        let mock = MockNotifications::start(vec![malformed.to_string(), playing.to_string()]);
+        let mut core = Core::new().unwrap();
+
+        let mut stream = NotificationStream::connect(mock.url().as_str(), &core.handle()).unwrap();
+        stream.set_reconnect_delay(Duration::from_millis(10));
+        // the mock sends a close frame after the messages but keeps the connection open,
+        // so the second notification is only received if the stream reconnects on the close
+        let notifications = core.run(stream.take(2).collect()).unwrap();
+        assert_eq!(notifications.len(), 2);
+        for notification in notifications {
+            match notification {
+                Notification::Playing(state) => assert_eq!(state.session_key, "12"),
+                other => panic!("unexpected notification {:?}", other),
+            }
+        }
+
+        let server_mock = MockServer::start();
+        let (core, server) = connect_mock(&server_mock);
+        let stream = server.notifications(&core.handle()).unwrap();
+        assert_eq!(
+            stream.url(),
+            format!(
+                "ws://{}/:/websockets/notifications?X-Plex-Token=mocktoken",
+                server_mock.addr()
+            )
+        );
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let dir = env::temp_dir().join(format!("plexapi-fixtures-{}", process::id()));
+        let mut core = Core::new().unwrap();
+        let client = https_client(&core);
+
+        let (sign_in_url, devices_url) = {
+            let mock = MockServer::start();
+            let mut plex_client = PlexClient::new(&client, "mocktoken".to_string());
+            plex_client.set_transport(Transport::record(&dir).unwrap());
+            let login = Login::new("mock", "secret");
+            let token = core.run(login.sign_in_with(&plex_client, mock.url(SIGN_IN).as_str()));
+            assert_eq!(token.unwrap(), "mocktoken");
+            let plex = Plex::from_client(plex_client, mock.url(DEVICES).as_str());
+            let device = core.run(plex.select_device("Mock")).unwrap();
+            let server = core.run(device.connect()).unwrap();
+            let library = core.run(server.library()).unwrap();
+            assert_eq!(core.run(library.sections()).unwrap().len(), 2);
+            (mock.url(SIGN_IN), mock.url(DEVICES))
+        };
+
+        for entry in fs::read_dir(&dir).unwrap() {
+            let mut content = String::new();
+            fs::File::open(entry.unwrap().path())
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            assert!(!content.contains("mocktoken"));
+        }
+
+        // the mock server is gone, everything has to be served from the fixtures
+        let mut plex_client = PlexClient::new(&client, "mocktoken".to_string());
+        plex_client.set_transport(Transport::replay(&dir).unwrap());
+        let login = Login::new("mock", "secret");
+        let token = core.run(login.sign_in_with(&plex_client, sign_in_url.as_str()));
+        assert_eq!(token.unwrap(), SCRUBBED);
+        let plex = Plex::from_client(plex_client, devices_url.as_str());
+        let device = core.run(plex.select_device("Mock")).unwrap();
+        let server = core.run(device.connect()).unwrap();
+        let library = core.run(server.library()).unwrap();
+        let movie_sections = core.run(library.movie_sections()).unwrap();
+        assert_eq!(movie_sections.len(), 1);
+
+        match core.run(movie_sections[0].all()) {
+            Err(APIError::UnmatchedRequest(key)) => {
+                assert_eq!(key, "GET /library/sections/1/all")
+            }
+            other => panic!("expected unmatched request, got {:?}", other.map(|v| v.len())),
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,498 @@
+use futures::future::{self, FutureResult};
+use futures::sync::oneshot;
+use futures::{Future, Sink, Stream};
+use hyper::{self, Method, StatusCode};
+use hyper::header::{ContentLength, ContentType};
+use hyper::server::{Http, Request, Response, Service};
+use std::fs::File;
+use std::io::Read;
+use std::net::{SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::str;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio_core::reactor::Core;
+use websocket::OwnedMessage;
+use tokio_core::net::TcpListener;
+use websocket::async::server::IntoWs;
+
+/// Gets replaced with the port the mock server is listening on when a fixture is served,
+/// so that fixtures can point back at the mock server, eg. the `Connection`s in `devices.xml`
+pub const PORT_PLACEHOLDER: &'static str = "{{port}}";
+
+/// mock for `http::routes::SIGNIN`
+pub const SIGN_IN: &'static str = "/users/sign_in.xml";
+
+/// mock for `http::routes::DEVICES`
+pub const DEVICES: &'static str = "/devices.xml";
+
+/// Directory that contains all the xml fixtures
+pub fn fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+/// Reads a fixture from the `fixtures` directory
+pub fn fixture(name: &str) -> String {
+    let path = fixtures_dir().join(name);
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .unwrap_or_else(|e| panic!("Failed to read fixture {:?}: {}", path, e));
+    content
+}
+
+/// A request the mock server received, tests can assert on them with `MockServer::requests`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Option<String>,
+    /// the `X-Plex-Container-Start` header of paged requests
+    pub container_start: Option<usize>,
+    /// the `X-Plex-Container-Size` header of paged requests
+    pub container_size: Option<usize>,
+}
+
+impl RecordedRequest {
+    fn from_request(req: &Request) -> Self {
+        let header = |name: &str| {
+            req.headers()
+                .get_raw(name)
+                .and_then(|raw| raw.one())
+                .and_then(|v| str::from_utf8(v).ok())
+                .and_then(|v| v.parse().ok())
+        };
+        RecordedRequest {
+            method: req.method().clone(),
+            path: req.path().to_string(),
+            query: req.query().map(|q| q.to_string()),
+            container_start: header("X-Plex-Container-Start"),
+            container_size: header("X-Plex-Container-Size"),
+        }
+    }
+
+    /// The value of the query parameter as it was sent, still url encoded
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .as_ref()
+            .and_then(|q| split_query(q).find(|&(k, _)| k == name))
+            .map(|(_, v)| v)
+    }
+}
+
+fn split_query<'a>(query: &'a str) -> Box<Iterator<Item = (&'a str, &'a str)> + 'a> {
+    Box::new(query.split('&').map(|pair| {
+        let mut split = pair.splitn(2, '=');
+        (split.next().unwrap_or(""), split.next().unwrap_or(""))
+    }))
+}
+
+#[derive(Debug, Clone)]
+struct Route {
+    method: Method,
+    path: String,
+    /// parameters the query of a request has to contain
+    params: Vec<(String, String)>,
+    /// the container start a paged request has to ask for
+    start: Option<usize>,
+    body: String,
+}
+
+impl Route {
+    fn new(method: Method, path: &str, body: &str) -> Self {
+        let mut split = path.splitn(2, '?');
+        let path = split.next().unwrap_or("").to_string();
+        let params = split
+            .next()
+            .map(|q| {
+                split_query(q)
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Route {
+            method,
+            path,
+            params,
+            start: None,
+            body: body.to_string(),
+        }
+    }
+
+    fn matches(&self, req: &RecordedRequest) -> bool {
+        self.method == req.method && self.path == req.path
+            && self.params
+                .iter()
+                .all(|&(ref k, ref v)| req.param(k) == Some(v.as_str()))
+            && self.start
+                .map_or(true, |start| req.container_start.unwrap_or(0) == start)
+    }
+
+    /// Routes with more constraints win over general ones
+    fn specificity(&self) -> usize {
+        self.params.len() + self.start.map_or(0, |_| 1)
+    }
+}
+
+/// Maps requests to the xml bodies that are served for them.
+///
+/// Routes match on the method and path. A query in the path of a route, eg.
+/// `/library/sections/2/all?type=8`, only matches requests that contain these parameters,
+/// and paged routes only match requests for their container start.
+#[derive(Debug, Clone, Default)]
+pub struct Routes {
+    routes: Vec<Route>,
+}
+
+impl Routes {
+    pub fn new() -> Self {
+        Routes::default()
+    }
+
+    /// Serves the fixture with the given file name for `GET` requests of the path
+    pub fn fixture(self, path: &str, name: &str) -> Self {
+        self.body(path, fixture(name).as_str())
+    }
+
+    /// Serves the body for `GET` requests of the path
+    pub fn body(self, path: &str, body: &str) -> Self {
+        self.method(Method::Get, path, body)
+    }
+
+    /// Serves the body for requests with the method
+    pub fn method(mut self, method: Method, path: &str, body: &str) -> Self {
+        self.routes.push(Route::new(method, path, body));
+        self
+    }
+
+    /// Serves the fixture for paged `GET` requests that start at `start`
+    pub fn page(mut self, path: &str, start: usize, name: &str) -> Self {
+        let mut route = Route::new(Method::Get, path, fixture(name).as_str());
+        route.start = Some(start);
+        self.routes.push(route);
+        self
+    }
+
+    fn find(&self, req: &RecordedRequest) -> Option<&Route> {
+        self.routes
+            .iter()
+            .filter(|r| r.matches(req))
+            .max_by_key(|r| r.specificity())
+    }
+
+    /// All routes necessary to run the
+    /// `Plex` -> `PlexDevice` -> `PlexServer` -> `PlexLibrary` -> `LibrarySection` chain
+    pub fn plex() -> Self {
+        Routes::new()
+            .method(Method::Post, SIGN_IN, fixture("sign_in.xml").as_str())
+            .fixture(DEVICES, "devices.xml")
+            .fixture("/", "server.xml")
+            .fixture("/identity", "identity.xml")
+            .fixture("/clients", "clients.xml")
+            .fixture("/:/prefs", "prefs.xml")
+            .method(Method::Put, "/:/prefs", "")
+            .fixture("/status/sessions", "sessions.xml")
+            .body("/status/sessions/terminate", "")
+            .fixture("/status/sessions/history/all", "history.xml")
+            .fixture("/activities", "activities.xml")
+            .method(Method::Delete, "/activities/1bd4a9a4-8e5b-4c3c-a6b4-8d1a0b9a3f10", "")
+            .fixture("/butler", "butler.xml")
+            .method(Method::Post, "/butler/BackupDatabase", "")
+            .method(Method::Delete, "/butler/BackupDatabase", "")
+            .fixture("/statistics/bandwidth", "bandwidth.xml")
+            .fixture("/statistics/resources", "resources.xml")
+            .fixture("/transcode/sessions", "transcode_sessions.xml")
+            .body("/video/:/transcode/universal/stop", "")
+            .method(Method::Put, "/updater/check", "")
+            .fixture("/updater/status", "updater.xml")
+            .method(Method::Put, "/updater/apply", "")
+            .body("/player/playback/play", "")
+            .body("/player/playback/pause", "")
+            .body("/player/playback/seekTo", "")
+            .body("/player/playback/setParameters", "")
+            .body("/player/playback/setStreams", "")
+            .body("/player/playback/playMedia", "")
+            .fixture("/player/timeline/poll", "timeline.xml")
+            .fixture("/hubs/search", "search.xml")
+            .fixture("/hubs", "hubs.xml")
+            .fixture("/hubs/home/recentlyAdded", "movies.xml")
+            .fixture("/hubs/sections/1", "section_hubs.xml")
+            .fixture("/library", "library.xml")
+            .fixture("/library/sections", "sections.xml")
+            .fixture("/library/sections/1/all", "movies.xml")
+            .fixture("/library/sections/2/albums", "albums.xml")
+            .fixture("/library/sections/2/all?type=8", "artists.xml")
+            .fixture("/library/sections/2/all?type=10", "artist_tracks.xml")
+            .fixture("/library/metadata/13", "artist.xml")
+            .fixture("/library/metadata/13/children", "albums.xml")
+            .fixture("/library/metadata/13/allLeaves", "artist_tracks.xml")
+            .fixture("/library/metadata/14", "album.xml")
+            .fixture("/library/metadata/14/children", "tracks.xml")
+            .fixture("/library/sections/3/all", "shows.xml")
+            .fixture("/library/metadata/100", "show.xml")
+            .fixture("/library/metadata/100/children", "seasons.xml")
+            .fixture("/library/metadata/100/allLeaves", "episodes.xml")
+            .fixture("/library/metadata/101", "season.xml")
+            .fixture("/library/metadata/101/children", "episodes.xml")
+            .fixture("/library/sections/4/all", "photos.xml")
+            .fixture("/library/sections/4/year", "years.xml")
+            .fixture("/library/metadata/210/children", "album_photos.xml")
+    }
+}
+
+struct MockService {
+    routes: Arc<Routes>,
+    port: Arc<AtomicUsize>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl Service for MockService {
+    type Request = Request;
+    type Response = Response;
+    type Error = hyper::Error;
+    type Future = FutureResult<Response, hyper::Error>;
+
+    fn call(&self, req: Request) -> Self::Future {
+        let recorded = RecordedRequest::from_request(&req);
+        let response = match self.routes.find(&recorded) {
+            Some(route) => {
+                let port = self.port.load(Ordering::SeqCst).to_string();
+                let body = route.body.replace(PORT_PLACEHOLDER, port.as_str());
+                Response::new()
+                    .with_header(ContentType::xml())
+                    .with_header(ContentLength(body.len() as u64))
+                    .with_body(body)
+            }
+            _ => Response::new().with_status(StatusCode::NotFound),
+        };
+        self.requests.lock().unwrap().push(recorded);
+        future::ok(response)
+    }
+}
+
+/// A Plex Media Server and plex.tv stand in that runs on its own thread on a local port.
+/// The server shuts down when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts a mock server that serves `Routes::plex`
+    pub fn start() -> Self {
+        MockServer::with_routes(Routes::plex())
+    }
+
+    pub fn with_routes(routes: Routes) -> Self {
+        let (addr_tx, addr_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let routes = Arc::new(routes);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let service_requests = Arc::clone(&requests);
+
+        let thread = thread::spawn(move || {
+            let local: SocketAddr = "127.0.0.1:0".parse().unwrap();
+            // the port is only known after binding
+            let port = Arc::new(AtomicUsize::new(0));
+            let service_port = Arc::clone(&port);
+            let server = Http::new()
+                .bind(&local, move || {
+                    Ok(MockService {
+                        routes: Arc::clone(&routes),
+                        port: Arc::clone(&service_port),
+                        requests: Arc::clone(&service_requests),
+                    })
+                })
+                .expect("Failed to bind mock server");
+            let addr = server.local_addr().unwrap();
+            port.store(addr.port() as usize, Ordering::SeqCst);
+            addr_tx.send(addr).unwrap();
+            server
+                .run_until(shutdown_rx.then(|_| Ok(())))
+                .expect("Mock server failed");
+        });
+
+        MockServer {
+            addr: addr_rx.recv().expect("Mock server did not start"),
+            requests,
+            shutdown: Some(shutdown_tx),
+            thread: Some(thread),
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The full url for the path on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// All requests received so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The requests received for the path, regardless of their method and query
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.path == path)
+            .collect()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A stand in for the notification websocket of a server.
+/// Every accepted connection gets sent a ping, all messages and a close frame. The connection
+/// is kept open afterwards, so clients have to act on the close frame themselves.
+pub struct MockNotifications {
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MockNotifications {
+    pub fn start(messages: Vec<String>) -> Self {
+        let (addr_tx, addr_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        let thread = thread::spawn(move || {
+            let mut core = Core::new().unwrap();
+            let handle = core.handle();
+            // the async websocket server does not expose its address, so it is set up by hand
+            let listener = TcpListener::bind(&"127.0.0.1:0".parse().unwrap(), &handle)
+                .expect("Failed to bind mock notifications");
+            addr_tx.send(listener.local_addr().unwrap()).unwrap();
+
+            let connections = listener.incoming().for_each(move |(stream, _)| {
+                let messages = messages.clone();
+                let connection = stream
+                    .into_ws()
+                    .map_err(|_| ())
+                    .and_then(|upgrade| upgrade.accept().map_err(|_| ()))
+                    .and_then(move |(client, _)| {
+                        let messages = Some(OwnedMessage::Ping(b"mock".to_vec()))
+                            .into_iter()
+                            .chain(messages.into_iter().map(OwnedMessage::Text))
+                            .chain(Some(OwnedMessage::Close(None)))
+                            .map(Ok::<_, ::websocket::WebSocketError>);
+                        client
+                            .send_all(::futures::stream::iter_result(messages))
+                            .map_err(|_| ())
+                    })
+                    .and_then(|(client, _)| future::empty::<(), ()>().map(move |_| drop(client)));
+                handle.spawn(connection.map(|_| ()));
+                Ok(())
+            });
+            let _ = core.run(connections.select2(shutdown_rx).then(|_| Ok::<(), ()>(())));
+        });
+
+        MockNotifications {
+            addr: addr_rx.recv().expect("Mock notifications did not start"),
+            shutdown: Some(shutdown_tx),
+            thread: Some(thread),
+        }
+    }
+
+    /// The websocket url of the notifications
+    pub fn url(&self) -> String {
+        format!("ws://{}/:/websockets/notifications", self.addr)
+    }
+}
+
+impl Drop for MockNotifications {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown.take() {
+            let _ = tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answers GDM searches on a loopback port like a server on the local network would
+pub struct MockGdmResponder {
+    addr: SocketAddr,
+    running: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MockGdmResponder {
+    /// Announces a server named `name` whose http port is `port`, every answer is sent twice
+    /// like servers with two network interfaces do
+    pub fn start(name: &str, port: u16) -> Self {
+        MockGdmResponder::with_response(format!(
+            "HTTP/1.0 200 OK\r\nContent-Type: plex/media-server\r\n\
+             Resource-Identifier: mockserveridentifier\r\nName: {}\r\nPort: {}\r\n\
+             Version: 1.13.5.5291-6fa5e50a8\r\n\r\n",
+            name, port
+        ))
+    }
+
+    /// Announces a player named `name` that, like some players, sends no Resource-Identifier
+    pub fn player(name: &str, port: u16) -> Self {
+        MockGdmResponder::with_response(format!(
+            "HTTP/1.0 200 OK\r\nContent-Type: plex/media-player\r\nName: {}\r\nPort: {}\r\n\r\n",
+            name, port
+        ))
+    }
+
+    fn with_response(response: String) -> Self {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("Failed to bind gdm responder");
+        socket
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_running = Arc::clone(&running);
+        let thread = thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            while thread_running.load(Ordering::SeqCst) {
+                if let Ok((len, src)) = socket.recv_from(&mut buf) {
+                    if buf[..len].starts_with(b"M-SEARCH") {
+                        for _ in 0..2 {
+                            let _ = socket.send_to(response.as_bytes(), src);
+                        }
+                    }
+                }
+            }
+        });
+
+        MockGdmResponder {
+            addr,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MockGdmResponder {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}